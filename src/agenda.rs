@@ -0,0 +1,153 @@
+use crate::Reminder;
+use chrono::{DateTime, Datelike, Days, Local, Months, NaiveDate};
+use std::io::IsTerminal;
+
+// most occurrences of a single reminder shown, every 1s over a month would flood the terminal
+const MAX_OCCURRENCES: usize = 500;
+// longest span, every day gets its own line even when nothing is due
+const MAX_DAYS: u32 = 366;
+
+// days agenda covers, starting today
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Span {
+    Today,
+    Tomorrow,
+    // next 7 days
+    #[default]
+    Week,
+    // until same day next month
+    Month,
+    Days(u32),
+    // both inclusive
+    Dates(NaiveDate, NaiveDate),
+}
+
+impl Span {
+    // first and last day, inclusive
+    pub fn dates(&self, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+        let days = |days: u32| {
+            let end = today.checked_add_days(Days::new(days.max(1) as u64 - 1));
+            (today, end.unwrap_or(NaiveDate::MAX))
+        };
+        match *self {
+            Self::Today => days(1),
+            Self::Tomorrow => {
+                let tomorrow = today.succ_opt().unwrap_or(today);
+                (tomorrow, tomorrow)
+            }
+            Self::Week => days(7),
+            Self::Month => {
+                let end = today.checked_add_months(Months::new(1)).unwrap_or(today);
+                (today, end.pred_opt().unwrap_or(end))
+            }
+            Self::Days(count) => days(count),
+            Self::Dates(start, end) => (start.min(end), start.max(end)),
+        }
+    }
+}
+
+impl std::str::FromStr for Span {
+    type Err = String;
+
+    // today, tomorrow, week, month, 10d, 2w or 2026-10-01..2026-10-14
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        let str = str.trim().to_lowercase();
+        let invalid = || {
+            "expected today, tomorrow, week, month, 10d, 2w or 2026-10-01..2026-10-14".to_owned()
+        };
+        let date = |date: &str| NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| invalid());
+        match str.as_str() {
+            "today" | "tdy" => return Ok(Self::Today),
+            "tomorrow" | "tmr" => return Ok(Self::Tomorrow),
+            "week" | "wk" | "w" => return Ok(Self::Week),
+            "month" | "mo" => return Ok(Self::Month),
+            _ => {}
+        }
+        let too_long = || format!("agenda covers at most {MAX_DAYS} days");
+        if let Some((start, end)) = str.split_once("..") {
+            let (start, end) = (date(start)?, date(end)?);
+            return match (end - start).num_days().unsigned_abs() < MAX_DAYS as u64 {
+                true => Ok(Self::Dates(start, end)),
+                false => Err(too_long()),
+            };
+        }
+        let (count, multiplier) = match str.strip_suffix('w') {
+            Some(weeks) => (weeks, 7),
+            None => (str.strip_suffix('d').unwrap_or(&str), 1),
+        };
+        match count.parse::<u32>() {
+            Ok(count) if count > 0 => match count.checked_mul(multiplier) {
+                Some(days) if days <= MAX_DAYS => Ok(Self::Days(days)),
+                _ => Err(too_long()),
+            },
+            _ => Err(invalid()),
+        }
+    }
+}
+
+// (time, reminder, skipped) for every occurrence within span, sorted by time
+pub fn occurrences(
+    reminders: &[Reminder],
+    span: Span,
+    now: DateTime<Local>,
+) -> Vec<(DateTime<Local>, &Reminder, bool)> {
+    let (start, end) = span.dates(now.date_naive());
+    let mut occurrences = Vec::new();
+    for reminder in reminders {
+        occurrences.extend(
+            reminder
+                .occurrences()
+                .take_while(|(time, _)| time.date_naive() <= end)
+                .filter(|(time, _)| time.date_naive() >= start)
+                .take(MAX_OCCURRENCES)
+                .map(|(time, skipped)| (time, reminder, skipped)),
+        );
+    }
+    occurrences.sort_by_key(|(time, _, _)| *time);
+    occurrences
+}
+
+// day by day timeline, skipped occurrences are dimmed
+pub fn print(reminders: &[Reminder], span: Span) {
+    let now = Local::now();
+    let (start, end) = span.dates(now.date_naive());
+    let occurrences = occurrences(reminders, span, now);
+    let color = std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+    let dim = |str: String| match color {
+        true => format!("\x1b[2m{str}\x1b[0m"),
+        false => str,
+    };
+    let mut day = start;
+    while day <= end {
+        // week header when agenda spans more than a day
+        if start != end && (day == start || day.weekday() == chrono::Weekday::Mon) {
+            let week = day.iso_week();
+            println!("week {} {}", week.week(), week.year());
+        }
+        println!("{}", day.format("  %a %y-%m-%d"));
+        let mut any = false;
+        for (time, reminder, skipped) in occurrences
+            .iter()
+            .filter(|(time, _, _)| time.date_naive() == day)
+        {
+            any = true;
+            let line = format!(
+                "    {} {} \"{}\"",
+                time.format("%H:%M"),
+                reminder.id,
+                reminder.title
+            );
+            match skipped {
+                true => println!("{}", dim(format!("{line} (skipped)"))),
+                false => println!("{line}"),
+            }
+        }
+        if !any {
+            println!("{}", dim("    -".to_owned()));
+        }
+        day = match day.succ_opt() {
+            Some(next) => next,
+            None => break,
+        };
+    }
+}
@@ -0,0 +1,42 @@
+// what daemon does with occurrences that came due while it was not running
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CatchUp {
+    // single notification summarizing how many times it was missed
+    #[default]
+    Once,
+    // one notification per missed occurrence
+    Each,
+    // missed occurrences are silently dropped
+    Drop,
+}
+
+impl CatchUp {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Once => "once",
+            Self::Each => "each",
+            Self::Drop => "drop",
+        }
+    }
+}
+
+impl std::str::FromStr for CatchUp {
+    type Err = String;
+
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        match str.to_lowercase().as_str() {
+            "once" | "summary" | "summarize" | "single" => Ok(Self::Once),
+            "each" | "all" | "every" => Ok(Self::Each),
+            "drop" | "none" | "ignore" | "silent" => Ok(Self::Drop),
+            _ => Err(format!(
+                "invalid catch-up policy \"{str}\", expected once, each or drop"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for CatchUp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
@@ -0,0 +1,178 @@
+use crate::{parse_time_of_day, CatchUp, Retention, Vocab};
+use chrono::NaiveTime;
+use std::path::Path;
+
+// user settings from config.toml next to default reminders file, everything is optional
+//
+// [defaults]
+// time = "9:00"             # time of day for reminders given only a date (feb 28, monday)
+// notification-timeout = 10 # seconds, 0 keeps notification until dismissed
+// catch-up = "each"
+// history-retention = "30d"
+// date-order = "dmy"        # 10/12 is 10 dec, default "mdy" is oct 12
+//
+// [aliases]
+// lunch = "12:30"
+// standup = 'work 9:15 repeat "standup"'
+//
+// [words]
+// woche = "week"
+//
+// [times]
+// morning = "7:30"
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Config {
+    pub default_time: Option<NaiveTime>,
+    pub notification_timeout: Option<u32>,
+    pub catch_up: Option<CatchUp>,
+    pub history_retention: Option<Retention>,
+    // date-order = "dmy" reads 10/12 as 10 dec instead of oct 12
+    pub day_first: bool,
+    // word and arguments it stands for
+    pub aliases: Vec<(String, Vec<String>)>,
+    // built in words plus [words] synonyms and [times] clock times
+    pub vocab: Vocab,
+}
+
+// splits on whitespace, keeping "quoted words" or 'quoted words' together
+fn split_args(str: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut arg = String::new();
+    let mut quote = None;
+    let mut quoted = false;
+    for c in str.chars() {
+        match (quote, c) {
+            (None, '"' | '\'') => (quote, quoted) = (Some(c), true),
+            (Some(q), c) if q == c => quote = None,
+            (None, c) if c.is_whitespace() => {
+                if !arg.is_empty() || quoted {
+                    args.push(std::mem::take(&mut arg));
+                }
+                quoted = false;
+            }
+            (_, c) => arg.push(c),
+        }
+    }
+    if !arg.is_empty() || quoted {
+        args.push(arg);
+    }
+    args
+}
+
+impl Config {
+    pub const FILE_NAME: &'static str = "config.toml";
+
+    // missing file is the same as empty one
+    pub fn load(file: &Path) -> Result<Self, String> {
+        match std::fs::read_to_string(file) {
+            Ok(config_str) => config_str
+                .parse()
+                .map_err(|e| format!("{}: {e}", file.display())),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    // replaces alias words with what they stand for, aliases don't expand inside other aliases
+    pub fn expand_aliases(&self, args: &[String]) -> Vec<String> {
+        let mut expanded = Vec::with_capacity(args.len());
+        for (i, arg) in args.iter().enumerate() {
+            let alias = self
+                .aliases
+                .iter()
+                .find(|(name, _)| i > 0 && name.eq_ignore_ascii_case(arg));
+            match alias {
+                Some((_, alias_args)) => expanded.extend(alias_args.iter().cloned()),
+                None => expanded.push(arg.clone()),
+            }
+        }
+        expanded
+    }
+
+    fn set_default(&mut self, key: &str, value: &toml::Value) -> Result<(), String> {
+        let invalid = || format!("invalid value {value} for \"{key}\"");
+        match (key, value) {
+            ("time", toml::Value::String(time)) => {
+                self.default_time =
+                    Some(parse_time_of_day(time, &Self::default()).ok_or_else(invalid)?)
+            }
+            ("notification-timeout", toml::Value::Integer(secs)) => {
+                self.notification_timeout = Some((*secs).try_into().map_err(|_| invalid())?)
+            }
+            ("catch-up", toml::Value::String(policy)) => self.catch_up = Some(policy.parse()?),
+            ("history-retention", toml::Value::String(retention)) => {
+                self.history_retention = Some(retention.parse()?)
+            }
+            ("history-retention", toml::Value::Integer(entries)) => {
+                self.history_retention = Some(Retention::Entries(
+                    (*entries).try_into().map_err(|_| invalid())?,
+                ))
+            }
+            ("date-order", toml::Value::String(order)) => {
+                self.day_first = match order.to_lowercase().as_str() {
+                    "dmy" | "day-first" => true,
+                    "mdy" | "month-first" => false,
+                    _ => return Err(format!("{} (expected \"dmy\" or \"mdy\")", invalid())),
+                }
+            }
+            (
+                "time" | "notification-timeout" | "catch-up" | "history-retention" | "date-order",
+                _,
+            ) => return Err(invalid()),
+            _ => return Err(format!("unknown config key \"defaults.{key}\"")),
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for Config {
+    type Err = String;
+
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        let table = str
+            .parse::<toml::Table>()
+            .map_err(|e| e.message().to_owned())?;
+        let mut config = Self::default();
+        for (key, value) in &table {
+            match (key.as_str(), value) {
+                ("defaults", toml::Value::Table(defaults)) => {
+                    for (key, value) in defaults {
+                        config.set_default(key, value)?;
+                    }
+                }
+                ("aliases", toml::Value::Table(aliases)) => {
+                    for (name, value) in aliases {
+                        let toml::Value::String(value) = value else {
+                            return Err(format!("alias \"{name}\" must be a string"));
+                        };
+                        if name.contains(char::is_whitespace) {
+                            return Err(format!("alias \"{name}\" must be a single word"));
+                        }
+                        config
+                            .aliases
+                            .push((name.to_lowercase(), split_args(value)));
+                    }
+                }
+                ("words", toml::Value::Table(words)) => {
+                    for (name, value) in words {
+                        let toml::Value::String(existing) = value else {
+                            return Err(format!("word \"{name}\" must be a string"));
+                        };
+                        config.vocab.add_synonym(name, existing)?;
+                    }
+                }
+                ("times", toml::Value::Table(times)) => {
+                    for (name, value) in times {
+                        let time = match value {
+                            toml::Value::String(time) => parse_time_of_day(time, &Self::default()),
+                            _ => None,
+                        };
+                        let time = time.ok_or(format!("invalid value {value} for \"{name}\""))?;
+                        config.vocab.set_day_part(name, time)?;
+                    }
+                }
+                _ => return Err(format!("unknown config key \"{key}\"")),
+            }
+        }
+        Ok(config)
+    }
+}
@@ -0,0 +1,172 @@
+use chrono::{Datelike, Duration, NaiveDateTime, NaiveTime, Timelike};
+
+// crontab schedule, either 5 fields (min hour day month weekday)
+// or 6 fields with leading seconds (sec min hour day month weekday)
+// fields accept *, ranges (1-5), steps (*/15, 10-30/5), lists (1,3,5) and names (jan, mon)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cron {
+    expr: String,
+    secs: u64,
+    mins: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    // sunday = 0
+    weekdays: u64,
+    // like cron, if both day and weekday are restricted either one matching is enough
+    days_any: bool,
+    weekdays_any: bool,
+}
+
+const MONTH_NAMES: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+// how far ahead to look before deciding schedule never fires (e.g. feb 30)
+const MAX_DAYS_AHEAD: u32 = 366 * 8;
+
+// parses single field into bitset of allowed values within min..=max
+// names are matched case insensitively and map to min + index
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<(u64, bool), String> {
+    let value = |str: &str| -> Result<u32, String> {
+        let lower = str.to_lowercase();
+        names
+            .iter()
+            .position(|name| *name == lower)
+            .map(|i| i as u32 + min)
+            .or_else(|| str.parse().ok())
+            .filter(|v| (min..=max).contains(v))
+            .ok_or(format!(
+                "invalid cron value \"{str}\", expected {min}-{max}"
+            ))
+    };
+    let mut bits = 0u64;
+    let any = field == "*" || field == "?";
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => (
+                range,
+                step.parse::<u32>()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or(format!("invalid cron step \"{step}\""))?,
+            ),
+            None => (item, 1),
+        };
+        let (start, end) = match range {
+            "*" | "?" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (value(start)?, value(end)?),
+                // 5/15 means 5-max/15
+                None if step > 1 => (value(range)?, max),
+                None => (value(range)?, value(range)?),
+            },
+        };
+        if start > end {
+            return Err(format!("invalid cron range \"{range}\""));
+        }
+        for v in (start..=end).step_by(step as usize) {
+            bits |= 1 << v;
+        }
+    }
+    Ok((bits, any))
+}
+
+fn has(bits: u64, v: u32) -> bool {
+    bits & (1 << v) != 0
+}
+
+impl Cron {
+    fn day_matches(&self, date: chrono::NaiveDate) -> bool {
+        if !has(self.months, date.month()) {
+            return false;
+        }
+        let day = has(self.days, date.day());
+        let weekday = has(self.weekdays, date.weekday().num_days_from_sunday());
+        match (self.days_any, self.weekdays_any) {
+            (false, false) => day || weekday,
+            _ => day && weekday,
+        }
+    }
+
+    // first matching time of day at or after `from`
+    fn time_from(&self, from: NaiveTime) -> Option<NaiveTime> {
+        for hour in (from.hour()..24).filter(|h| has(self.hours, *h)) {
+            let min_start = if hour == from.hour() {
+                from.minute()
+            } else {
+                0
+            };
+            for min in (min_start..60).filter(|m| has(self.mins, *m)) {
+                let same_min = hour == from.hour() && min == from.minute();
+                let sec_start = if same_min { from.second() } else { 0 };
+                if let Some(sec) = (sec_start..60).find(|s| has(self.secs, *s)) {
+                    return NaiveTime::from_hms_opt(hour, min, sec);
+                }
+            }
+        }
+        None
+    }
+
+    // next time strictly after `after` this schedule fires
+    pub fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut from = after.with_nanosecond(0)? + Duration::seconds(1);
+        for _ in 0..MAX_DAYS_AHEAD {
+            if self.day_matches(from.date()) {
+                if let Some(time) = self.time_from(from.time()) {
+                    return Some(from.date().and_time(time));
+                }
+            }
+            from = (from.date() + Duration::days(1)).and_time(NaiveTime::MIN);
+        }
+        None
+    }
+}
+
+impl std::str::FromStr for Cron {
+    type Err = String;
+
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        let expr = str.split_whitespace().collect::<Vec<_>>().join(" ");
+        let expanded = match expr.to_lowercase().as_str() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            _ => &expr,
+        };
+        let fields: Vec<&str> = expanded.split(' ').collect();
+        let fields = match fields.len() {
+            5 => [&["0"], fields.as_slice()].concat(),
+            6 => fields,
+            n => {
+                return Err(format!(
+                    "cron expression \"{expr}\" has {n} fields, expected 5 or 6"
+                ))
+            }
+        };
+        // 7 is also sunday
+        let (weekdays, weekdays_any) = parse_field(fields[5], 0, 7, &WEEKDAY_NAMES)?;
+        let weekdays = (weekdays | (weekdays >> 7)) & 0x7f;
+        let (days, days_any) = parse_field(fields[3], 1, 31, &[])?;
+        Ok(Self {
+            secs: parse_field(fields[0], 0, 59, &[])?.0,
+            mins: parse_field(fields[1], 0, 59, &[])?.0,
+            hours: parse_field(fields[2], 0, 23, &[])?.0,
+            days,
+            months: parse_field(fields[4], 1, 12, &MONTH_NAMES)?.0,
+            weekdays,
+            days_any,
+            weekdays_any,
+            expr,
+        })
+    }
+}
+
+impl std::fmt::Display for Cron {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.expr)
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{Error, ReminderFile};
+use notify::{RecursiveMode, Watcher};
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc::RecvTimeoutError,
+};
+
+// upper bound on how long daemon sleeps without looking at the clock,
+// wall clock can jump ahead (suspend, time sync) while the timeout is monotonic
+const MAX_SLEEP: std::time::Duration = std::time::Duration::from_secs(60);
+
+// reminder lists in lists dir, sorted by name
+pub fn list_files(lists_dir: &Path) -> Vec<PathBuf> {
+    let mut files = std::fs::read_dir(lists_dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .collect::<Vec<_>>();
+    files.sort();
+    files
+}
+
+// notifies reminders of every file as they come due, never returns unless watcher dies
+// lists created in lists dir while running are picked up with open_list
+// files that fail to load or save are reported and retried on their next change
+pub fn run(
+    mut files: Vec<ReminderFile>,
+    lists_dir: Option<PathBuf>,
+    open_list: impl Fn(&Path) -> ReminderFile,
+) -> Result<(), Error> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).map_err(|e| {
+        let path = files.first().map_or(Path::new("."), |file| file.path());
+        Error::storage(path, format!("failed to watch for changes: {e}"))
+    })?;
+    // saves replace files, so watch their folders rather than files themselves
+    let mut dirs = files
+        .iter()
+        .map(|file| match file.path().parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_owned(),
+            _ => ".".into(),
+        })
+        .collect::<Vec<_>>();
+    if let Some(lists_dir) = &lists_dir {
+        std::fs::create_dir_all(lists_dir).unwrap_or_default();
+        dirs.push(lists_dir.clone());
+    }
+    dirs.sort();
+    dirs.dedup();
+    for dir in &dirs {
+        if let Err(e) = watcher.watch(dir, RecursiveMode::NonRecursive) {
+            eprintln!("failed to watch {}: {e}", dir.display());
+        }
+    }
+    for file in &mut files {
+        if let Err(e) = file.start_daemon() {
+            eprintln!("{e}");
+        }
+    }
+    loop {
+        // block until a file changes or closest reminder is due, whichever comes first
+        let until = files.iter().filter_map(|file| file.time_until_next()).min();
+        let res = match until {
+            Some(until) => match rx.recv_timeout(until.min(MAX_SLEEP)) {
+                Ok(res) => Some(res),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => return Ok(()),
+            },
+            None => match rx.recv() {
+                Ok(res) => Some(res),
+                Err(_) => return Ok(()),
+            },
+        };
+        let mut events = Vec::new();
+        for res in res
+            .into_iter()
+            .chain(std::iter::from_fn(|| rx.try_recv().ok()))
+        {
+            match res {
+                Ok(event) => events.push(event),
+                Err(e) => eprintln!("{e}"),
+            }
+        }
+        if let Some(lists_dir) = lists_dir.as_ref().filter(|_| !events.is_empty()) {
+            for path in list_files(lists_dir) {
+                if !files.iter().any(|file| file.path() == path) {
+                    let mut file = open_list(&path);
+                    if let Err(e) = file.start_daemon() {
+                        eprintln!("{e}");
+                    }
+                    files.push(file);
+                }
+            }
+        }
+        for file in &mut files {
+            let changed = events.iter().any(|event| file.is_file_event(event));
+            let due = file.time_until_next().is_some_and(|until| until.is_zero());
+            if changed || due {
+                if let Err(e) = file.refresh() {
+                    eprintln!("{e}");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use std::path::Path;
+
+// everything that stops a command, each kind exits with its own code so scripts can tell them apart
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    // argument that can't be understood (25:00, bad rrule or cron)
+    Parse {
+        arg: String,
+        message: String,
+        suggestion: Option<String>,
+    },
+    // arguments make sense, but describe time that doesn't exist or never comes (feb 30)
+    Schedule {
+        message: String,
+        suggestion: Option<String>,
+    },
+    // reminders file, its lock or data folder can't be read or written
+    Storage {
+        path: String,
+        message: String,
+    },
+}
+
+impl Error {
+    pub fn parse(arg: &str, message: impl Into<String>) -> Self {
+        Self::Parse {
+            arg: arg.to_owned(),
+            message: message.into(),
+            suggestion: None,
+        }
+    }
+
+    pub fn schedule(message: impl Into<String>) -> Self {
+        Self::Schedule {
+            message: message.into(),
+            suggestion: None,
+        }
+    }
+
+    pub fn storage(path: impl AsRef<Path>, message: impl ToString) -> Self {
+        Self::Storage {
+            path: path.as_ref().display().to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    // shown on its own line below the error: "did you mean feb 28?" or "try: remind ..."
+    pub fn suggest(mut self, new: impl Into<String>) -> Self {
+        if let Self::Parse { suggestion, .. } | Self::Schedule { suggestion, .. } = &mut self {
+            *suggestion = Some(new.into());
+        }
+        self
+    }
+
+    // 1 is left for panics and other unexpected failures
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Self::Parse { .. } => 2,
+            Self::Schedule { .. } => 3,
+            Self::Storage { .. } => 4,
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let suggestion = match self {
+            Self::Parse {
+                arg,
+                message,
+                suggestion,
+            } => {
+                write!(f, "invalid \"{arg}\": {message}")?;
+                suggestion
+            }
+            Self::Schedule {
+                message,
+                suggestion,
+            } => {
+                write!(f, "{message}")?;
+                suggestion
+            }
+            Self::Storage { path, message } => return write!(f, "{path}: {message}"),
+        };
+        match suggestion {
+            Some(suggestion) => write!(f, "\n  {suggestion}"),
+            None => Ok(()),
+        }
+    }
+}
@@ -1,4 +1,4 @@
-#[derive(Default, PartialEq, Eq)]
+#[derive(Default, Clone, PartialEq, Eq)]
 pub struct Interval {
     pub secs: u32,
     pub mins: u32,
@@ -9,15 +9,26 @@ pub struct Interval {
 }
 
 impl Interval {
-    pub fn deserialize(str: &str) -> Self {
-        Self {
-            secs: str[15..17].parse().unwrap(),
-            mins: str[12..14].parse().unwrap(),
-            hours: str[9..11].parse().unwrap(),
-            days: str[6..8].parse().unwrap(),
-            months: str[3..5].parse().unwrap(),
-            years: str[0..2].parse().unwrap(),
-        }
+    // parses "yy-mm-dd hh:mm:ss" written by serialize
+    pub fn deserialize(str: &str) -> Result<Self, String> {
+        let parts = str
+            .split(['-', ' ', ':'])
+            .map(|part| part.parse::<u32>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| format!("invalid interval \"{str}\""))?;
+        let [years, months, days, hours, mins, secs] = parts[..] else {
+            return Err(format!(
+                "invalid interval \"{str}\", expected yy-mm-dd hh:mm:ss"
+            ));
+        };
+        Ok(Self {
+            secs,
+            mins,
+            hours,
+            days,
+            months,
+            years,
+        })
     }
 
     pub fn serialize(&self) -> String {
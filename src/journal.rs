@@ -0,0 +1,413 @@
+use crate::{escape, unescape, Reminder};
+use chrono::{Duration, Local, NaiveDateTime};
+use std::{fs::OpenOptions, io::Write, path::Path};
+
+// kind of change an entry records
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Add,
+    Remove,
+    Skip,
+    Fire,
+    Edit,
+    Clear,
+    // doctor fix
+    Repair,
+    // reverts changes of target entry
+    Undo,
+    // reapplies changes of target entry
+    Redo,
+}
+
+impl Op {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Add => "add",
+            Self::Remove => "remove",
+            Self::Skip => "skip",
+            Self::Fire => "fire",
+            Self::Edit => "edit",
+            Self::Clear => "clear",
+            Self::Repair => "repair",
+            Self::Undo => "undo",
+            Self::Redo => "redo",
+        }
+    }
+}
+
+impl std::str::FromStr for Op {
+    type Err = String;
+
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        [
+            Self::Add,
+            Self::Remove,
+            Self::Skip,
+            Self::Fire,
+            Self::Edit,
+            Self::Clear,
+            Self::Repair,
+            Self::Undo,
+            Self::Redo,
+        ]
+        .into_iter()
+        .find(|op| op.as_str() == str)
+        .ok_or(format!("unknown operation \"{str}\""))
+    }
+}
+
+// who made the change
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Actor {
+    #[default]
+    Cli,
+    Daemon,
+}
+
+impl Actor {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Cli => "cli",
+            Self::Daemon => "daemon",
+        }
+    }
+}
+
+// single reminder before and after change, None when added or removed
+#[derive(Clone)]
+pub struct Change {
+    pub before: Option<Reminder>,
+    pub after: Option<Reminder>,
+}
+
+impl Change {
+    pub fn inverse(&self) -> Self {
+        Self {
+            before: self.after.clone(),
+            after: self.before.clone(),
+        }
+    }
+}
+
+pub struct Entry {
+    pub seq: u64,
+    pub time: NaiveDateTime,
+    pub actor: Actor,
+    pub op: Op,
+    // entry undone or redone by this one
+    pub target: Option<u64>,
+    pub changes: Vec<Change>,
+}
+
+impl Entry {
+    // one line of tab separated key=value fields, changes are before/after pairs
+    // holding escaped reminder lines, empty when reminder did not exist
+    fn serialize(&self) -> String {
+        let mut fields = vec![
+            format!("seq={}", self.seq),
+            format!("time={}", self.time.format(Journal::TIME_FORMAT)),
+            format!("actor={}", self.actor.as_str()),
+            format!("op={}", self.op.as_str()),
+        ];
+        if let Some(target) = self.target {
+            fields.push(format!("target={target}"));
+        }
+        let reminder_str = |reminder: &Option<Reminder>| {
+            reminder
+                .as_ref()
+                .map(|rem| escape(rem.serialize().trim_end()))
+                .unwrap_or_default()
+        };
+        for change in &self.changes {
+            fields.push(format!("before={}", reminder_str(&change.before)));
+            fields.push(format!("after={}", reminder_str(&change.after)));
+        }
+        fields.join("\t") + "\n"
+    }
+
+    fn deserialize(line: &str) -> Result<Self, String> {
+        let mut entry = Entry {
+            seq: 0,
+            time: NaiveDateTime::default(),
+            actor: Actor::Cli,
+            op: Op::Add,
+            target: None,
+            changes: Vec::new(),
+        };
+        let reminder = |value: &str| -> Result<Option<Reminder>, String> {
+            match value {
+                "" => Ok(None),
+                _ => Reminder::deserialize(&unescape(value)?).map(Some),
+            }
+        };
+        for field in line.split('\t') {
+            let (key, value) = field
+                .split_once('=')
+                .ok_or(format!("field \"{field}\" is missing '='"))?;
+            let invalid = |_| format!("invalid {key} \"{value}\"");
+            match key {
+                "seq" => entry.seq = value.parse().map_err(invalid)?,
+                "time" => {
+                    entry.time = NaiveDateTime::parse_from_str(value, Journal::TIME_FORMAT)
+                        .map_err(|_| format!("invalid time \"{value}\""))?
+                }
+                "actor" => {
+                    entry.actor = match value {
+                        "cli" => Actor::Cli,
+                        "daemon" => Actor::Daemon,
+                        _ => return Err(format!("unknown actor \"{value}\"")),
+                    }
+                }
+                "op" => entry.op = value.parse()?,
+                "target" => entry.target = Some(value.parse().map_err(invalid)?),
+                "before" => entry.changes.push(Change {
+                    before: reminder(value)?,
+                    after: None,
+                }),
+                "after" => match entry.changes.last_mut() {
+                    Some(change) if change.after.is_none() => change.after = reminder(value)?,
+                    _ => return Err("after without before".to_owned()),
+                },
+                // fields added by newer versions
+                _ => {}
+            }
+        }
+        Ok(entry)
+    }
+
+    // titles of changed reminders, with renames shown as old -> new
+    pub fn summary(&self) -> String {
+        let titles = self
+            .changes
+            .iter()
+            .map(|change| match (&change.before, &change.after) {
+                (Some(before), Some(after)) if before.title != after.title => {
+                    format!("\"{}\" -> \"{}\"", before.title, after.title)
+                }
+                (_, Some(reminder)) | (Some(reminder), None) => format!("\"{}\"", reminder.title),
+                (None, None) => String::new(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        match self.target {
+            Some(target) => format!("{} #{target} {titles}", self.op.as_str()),
+            None => format!("{} {titles}", self.op.as_str()),
+        }
+    }
+}
+
+impl std::fmt::Display for Entry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "#{} {} ({})",
+            self.seq,
+            self.summary(),
+            self.time.format("%y-%m-%d %H:%M:%S")
+        )
+    }
+}
+
+// changes between two versions of reminders, matched by id
+pub fn diff(before: &[Reminder], after: &[Reminder]) -> Vec<Change> {
+    let mut changes = Vec::new();
+    for reminder in before {
+        match after.iter().find(|rem| rem.id == reminder.id) {
+            Some(new) if new.serialize() == reminder.serialize() => {}
+            new => changes.push(Change {
+                before: Some(reminder.clone()),
+                after: new.cloned(),
+            }),
+        }
+    }
+    for reminder in after {
+        if !before.iter().any(|rem| rem.id == reminder.id) {
+            changes.push(Change {
+                before: None,
+                after: Some(reminder.clone()),
+            });
+        }
+    }
+    changes
+}
+
+// moves reminder from base towards target, fields that changed since base
+// (e.g. end and repeats after daemon fired) keep their current value
+pub fn merge(base: &Reminder, current: &Reminder, target: &Reminder) -> Reminder {
+    let fields = |rem: &Reminder| {
+        rem.serialize()
+            .trim_end()
+            .split('\t')
+            .filter_map(|field| field.split_once('='))
+            .map(|(key, value)| (key.to_owned(), value.to_owned()))
+            .collect::<Vec<_>>()
+    };
+    let current_reminder = current;
+    let (base, current, target) = (fields(base), fields(current), fields(target));
+    let get = |fields: &[(String, String)], key: &str| {
+        fields
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.clone())
+    };
+    let mut keys: Vec<&String> = Vec::new();
+    for (key, _) in target.iter().chain(&current).chain(&base) {
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+    let line = keys
+        .into_iter()
+        .filter_map(|key| {
+            let value = if get(&current, key) == get(&base, key) {
+                get(&target, key)
+            } else {
+                get(&current, key)
+            };
+            value.map(|value| format!("{key}={value}"))
+        })
+        .collect::<Vec<_>>()
+        .join("\t");
+    Reminder::deserialize(&line).unwrap_or_else(|_| current_reminder.clone())
+}
+
+// entries that can be undone and entries that can be redone, most recent last
+pub fn undo_redo_stacks(entries: &[Entry]) -> (Vec<u64>, Vec<u64>) {
+    let mut done = Vec::new();
+    let mut undone = Vec::new();
+    for entry in entries {
+        match (entry.op, entry.target) {
+            (Op::Undo, Some(target)) => {
+                done.retain(|seq| *seq != target);
+                undone.push(target);
+            }
+            (Op::Redo, Some(target)) => {
+                undone.retain(|seq| *seq != target);
+                done.push(target);
+            }
+            // daemon firing in between does not make undone changes unreachable
+            (Op::Fire, _) => done.push(entry.seq),
+            _ => {
+                done.push(entry.seq);
+                undone.clear();
+            }
+        }
+    }
+    (done, undone)
+}
+
+// how much of the journal to keep
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Retention {
+    Entries(usize),
+    Days(u32),
+}
+
+impl Default for Retention {
+    fn default() -> Self {
+        Self::Entries(1000)
+    }
+}
+
+impl std::str::FromStr for Retention {
+    type Err = String;
+
+    // "500" entries or "30d" days
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        let str = str.trim().to_lowercase();
+        let invalid =
+            || format!("invalid retention \"{str}\", expected entries (500) or days (30d)");
+        match str.strip_suffix("days").or(str.strip_suffix('d')) {
+            Some(days) => days.trim().parse().map(Self::Days).map_err(|_| invalid()),
+            None => str.parse().map(Self::Entries).map_err(|_| invalid()),
+        }
+    }
+}
+
+// append-only log of changes to reminders, undo/redo and history are derived from it
+pub struct Journal {
+    file: String,
+    retention: Retention,
+}
+
+impl Journal {
+    const HEADER: &'static str = "# remind journal v1";
+    const TIME_FORMAT: &'static str = "%Y-%m-%d %H:%M:%S%.3f";
+
+    pub fn new<P: AsRef<Path>>(file: P) -> Self {
+        Self {
+            file: file.as_ref().to_string_lossy().to_string(),
+            retention: Retention::default(),
+        }
+    }
+
+    pub fn set_retention(&mut self, retention: Retention) {
+        self.retention = retention;
+    }
+
+    // unreadable lines are reported and skipped
+    pub fn load(&self) -> Vec<Entry> {
+        let journal_str = std::fs::read_to_string(&self.file).unwrap_or_default();
+        let mut entries = Vec::new();
+        for (i, line) in journal_str.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match Entry::deserialize(line) {
+                Ok(entry) => entries.push(entry),
+                Err(e) => eprintln!("{}: line {}: {e}", self.file, i + 1),
+            }
+        }
+        entries
+    }
+
+    pub fn append(&self, actor: Actor, op: Op, target: Option<u64>, changes: Vec<Change>) {
+        let mut entries = self.load();
+        let entry = Entry {
+            seq: entries.last().map_or(1, |last| last.seq + 1),
+            time: Local::now().naive_local(),
+            actor,
+            op,
+            target,
+            changes,
+        };
+        let line = entry.serialize();
+        entries.push(entry);
+        let keep = match self.retention {
+            Retention::Entries(count) => count,
+            Retention::Days(days) => {
+                let oldest = Local::now().naive_local() - Duration::days(days as i64);
+                entries.iter().filter(|entry| entry.time >= oldest).count()
+            }
+        };
+        if entries.len() > keep {
+            self.rewrite(&entries[entries.len() - keep..]);
+            return;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.file);
+        let result = file.and_then(|mut file| {
+            if file.metadata()?.len() == 0 {
+                writeln!(file, "{}", Self::HEADER)?;
+            }
+            file.write_all(line.as_bytes())
+        });
+        if let Err(e) = result {
+            eprintln!("failed to write {}: {e}", self.file);
+        }
+    }
+
+    fn rewrite(&self, entries: &[Entry]) {
+        let entries_str = entries.iter().map(Entry::serialize).collect::<String>();
+        let contents = format!("{}\n{entries_str}", Self::HEADER);
+        // own temp name, reminders.tmp belongs to the reminders file saves
+        let tmp_file = Path::new(&self.file).with_extension("journal.tmp");
+        let result = std::fs::write(&tmp_file, contents)
+            .and_then(|()| std::fs::rename(&tmp_file, &self.file));
+        if let Err(e) = result {
+            eprintln!("failed to save {}: {e}", self.file);
+        }
+    }
+}
@@ -1,4 +1,6 @@
-use chrono::{Datelike, Duration, Local, Months, NaiveTime, Timelike};
+use chrono::{
+    Datelike, Duration, FixedOffset, Local, Months, NaiveDate, NaiveTime, TimeZone, Timelike,
+};
 mod time_unit;
 use time_unit::*;
 mod reminder;
@@ -7,12 +9,24 @@ mod reminder_file;
 use reminder_file::*;
 mod interval;
 use interval::*;
-
-// (small) TODO:
-// - undo not perfect when reminder expires
-// - remind every next day "does not do how it sounds"
-// - remind every other day "does not exist"
-// - remind after 2 days
+mod catch_up;
+use catch_up::*;
+mod rrule;
+use rrule::*;
+mod cron;
+use cron::*;
+mod journal;
+use journal::{Op, Retention};
+mod config;
+mod daemon;
+use config::Config;
+mod vocab;
+use std::path::{Path, PathBuf};
+use vocab::{Vocab, Word};
+mod error;
+use error::Error;
+mod agenda;
+use agenda::Span;
 
 #[derive(Debug, Clone, PartialEq)]
 enum Arg {
@@ -24,92 +38,116 @@ enum Arg {
     WeekDay(u8),
     Time(u32, u32, u32), // hour, min, sec
     Month(u32),
+    Date(NaiveDate),              // with year, 2026-10-20
+    Window(NaiveTime, NaiveTime), // between 9am and 5pm
     Skip(u32),
-    Next(u32),
+    Next(u32), // next friday, next week
+    CatchUp(CatchUp),
+    RRule(RRule),
+    Cron(Cron),
+    Missed,
+    Doctor(bool), // fix
+    Edit(String), // id or fuzzy title
+    Explain,
+    Agenda(Span),
     Undo,
+    UnFire,
+    Redo,
+    History,
     Clear,
     List,
     Help,
 }
 
-fn print_help() {
-    let help_str = r#"
+fn print_help(vocab: &Vocab) {
+    let examples_str = r#"
 Examples:
     Add Reminders:
         $ remind 1d "code tomorrow"
         $ remind minute "egg ready" repeat 4
+        $ remind 1h30m "laundry" # also 2d4h15m, 1w2d, PT1H30M
         $ remind 12:30:15 feb 28 2029
+        $ remind 2029-02-28T12:30 # also 2029-02-28T12:30+02:00, 28.02.2029, 02/28, 28 feb
         $ remind monday fri "study"
         $ remind weekly work "go to work" # 5 days a week, at current time
         $ remind weekend "rest" rep 8
         $ remind skip 2 "rest" # skip 2 weekends cause boss sucks
         $ remind daily 11am workout
+        $ remind in 2 hours "tea"
+        $ remind every other day "water plants"
+        $ remind next friday 5pm "drinks" # a week after this friday
+        $ remind the day after tomorrow "dentist"
+        $ remind tomorrow morning "gym" # also noon, afternoon, evening, tonight, eod
+        $ remind hourly "stretch" catch-up drop
+        $ remind every 30 minutes between 9am and 5pm on workdays "drink water"
+        $ remind 9:00 "FREQ=MONTHLY;BYDAY=2TU" "pay rent" # RFC 5545 recurrence rule
+        $ remind cron "0 9 * * 1-5" "standup" # crontab, 6 fields for seconds
         $ remind undo
+        $ remind undo fire # re-arm last notification
+        $ remind redo
+        $ remind history
 
     List Reminders:
         $ remind list
+        $ remind list --expand 3 # next 3 fire times of each, skipped ones marked
+        $ remind next kq27 10 # next 10 fire times of one reminder
+        $ remind agenda tomorrow # day by day timeline, also today, week (default), month, 10d
+        $ remind agenda 2026-12-20..2027-01-05
+
+    Edit Reminders (by id from list, or fuzzy title):
+        $ remind edit kq27 3pm # only changes time of day
+        $ remind edit "rest" rep 4 "sleep in"
+
+    Explain (how arguments are understood and when reminder would fire, nothing is saved):
+        $ remind explain monday 3pm rep 2 "call mom"
+        $ remind edit kq27 daily --dry-run
+
+    Lists (each list is its own file, daemon watches all of them):
+        $ remind -l work 9am "standup"
+        $ remind list -l work
+        $ remind --file ~/notes/reminders.txt list
+        - REMIND_FILE=path env var moves default reminders file, lists are kept next to it
 
-    Remove Reminders (fuzzy):
+    Remove Reminders (by id from list, or fuzzy title):
+        $ remind rm kq27
         $ remind rm "some long name..."
         $ remind clear
 
-Aliases:
-    Time:
-        - s[econds] | sc | secs | scnds | secndo | sencod | secodn | secnod
-        - m[inutes] | mn | mns | mintue | minteu | mitneu
-        - h[ours] | hr | hrs | hs | horus
-        - d[ays] | ds
-        - tomorrow | tomorow | tommorow | tommorrow
-        - next | after
-        - w[eeks] | wk | wks
-        - mo | mont | month | months | mnth | mnths
-        - y[ears] | yr | yrs | ys
-    
-    Weekday:
-        - su[nday] | sn | snd
-        - mon[day] | md | mn | mnd
-        - tu[esday] | tsd
-        - we[dnesday] | wd | wdnsd
-        - th[ursday] | thrsd
-        - fr[iday] | fd | frd
-        - mon,thu | mo-th | mo|th | mo+th | mo-th | mo/th | mo/th | mo\th | mo_th
-        - work | wrk | business | biz | busy | workweek | work-week | su,mo,tu,we,th,fr,sa
-        - weeke[nd] | weeknd | wknd | wkd | break | brk | holiday| rest | week-end | sun,sat | sa+su
-    
-    Month:
-        - january | janu | jan
-        - february | febr | feb
-        - march | marc | mar
-        - april | aprl | apr
-        - may
-        - june | jun
-        - july | jul
-        - august | augu | aug
-        - september | sept | sep
-        - october | octo | oct
-        - november | nove | nov
-        - december | dece | dec
-    
-    Repeat:
-        - rep[eat] | rp | times
-        - repeating | repetetive | every | loop | looping | infinite | ongoing | recurring | cyclic | series
-        - skip | sk | skp | snooze | snz | skip-next | sk-next | skp-next | snooze-next | snz-next
-        - hourly | daily | weekly | monthly
-        - yearly | annual | annually | anual | anually
-        - 1x, x1, 2x, x2...
-        - twice
-
-    Numbers:
-        - one, two, three, four, five, six, seven, eight, nine, ten
-
-    Commands:
-        - undo | goback | go-back
-        - clear | clean | cls | clr | remove-all | rm-all | del-all | delete-all | erase-all | rmv-all | dlt-all  
-        - r[emove] | rm | rmv | de[lete] | dl | dlt | erase | forget | forgt | frgt
-        - l[ist] | ls | reminders | all | see | everything
-        - h | help | hlp
 "#;
-    println!("{help_str}");
+    let sections_str = r#"    Catch-up (what to do with reminders missed while daemon was not running):
+        - catch-up once (default, one notification saying how many were missed)
+        - catch-up each (one notification per missed reminder)
+        - catch-up drop (silently drop missed reminders)
+        - REMIND_CATCH_UP=each env var sets the default for the daemon
+
+    Config (config.toml next to reminders file, env vars take precedence):
+        [defaults]
+        time = "9:00"             # time of day for reminders given only a date (feb 28, monday)
+        notification-timeout = 10 # seconds, 0 keeps notification until dismissed
+        catch-up = "each"
+        history-retention = "30d"
+        date-order = "dmy"        # 10/12 is 10 dec, default "mdy" is oct 12
+
+        [aliases]
+        lunch = "12:30"
+        standup = 'work 9:15 repeat "standup"'
+
+        [words]                   # extra words for built in ones
+        woche = "week"
+
+        [times]                   # clock times of morning, evening...
+        morning = "7:30"
+
+    Exit codes:
+        - 2 argument can't be understood (25:00)
+        - 3 time doesn't exist or already passed (feb 30)
+        - 4 reminders file can't be read or written
+
+    History (kept in reminders.journal next to reminders.txt):
+        - REMIND_HISTORY_RETENTION=500 keeps last 500 changes (default 1000)
+        - REMIND_HISTORY_RETENTION=30d keeps changes from last 30 days
+"#;
+    println!("{examples_str}{}{sections_str}", vocab.help());
 }
 
 // 32week4 -> (32, week, 4)
@@ -130,125 +168,500 @@ fn num_str_num(str: &str) -> (u32, String, u32) {
     (first_dig, str[first_idx..last_idx].to_owned(), last_dig)
 }
 
-fn tokenize(args: &[String]) -> Vec<Arg> {
+// time of day written the way tokenize understands it (9am, 12:30, 18:00:15, noon)
+// config gives day parts their clock times
+fn parse_time_of_day(str: &str, config: &Config) -> Option<NaiveTime> {
+    let args = [String::new(), str.to_owned()];
+    match tokenize(&args, config).as_deref() {
+        Ok([Arg::Time(h, m, s)]) => NaiveTime::from_hms_opt(*h, *m, *s),
+        _ => None,
+    }
+}
+
+// 1st, 2nd, 3rd, 28th
+fn ordinal(str: &str) -> Option<u32> {
+    let digits = str.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    match str[digits.len()..].to_lowercase().as_str() {
+        "st" | "nd" | "rd" | "th" if !digits.is_empty() => digits.parse().ok(),
+        _ => None,
+    }
+}
+
+// every 3 days, every other day, every 3rd week: how many units apart
+fn step_count(str: &str, vocab: &Vocab) -> Option<u32> {
+    let str = str.to_lowercase();
+    match str.as_str() {
+        "next" => Some(1),
+        "other" | "second" => Some(2),
+        "third" => Some(3),
+        _ => str
+            .parse()
+            .ok()
+            .or_else(|| ordinal(&str))
+            .or_else(|| match vocab.lookup(&str) {
+                Some(Word::Number(num)) => Some(num),
+                _ => None,
+            }),
+    }
+}
+
+// 1h30m, 2d4h15m, 1w2d and iso 8601 PT1H30M, P1DT12H, units go largest first
+// None if str isn't a compound duration (3d, 2x, 3rd)
+fn parse_duration(str: &str, vocab: &Vocab) -> Result<Option<Vec<TimeUnit>>, Error> {
+    let lower = str.to_lowercase();
+    // (number, unit, name it was written as, empty for iso)
+    let mut units = Vec::new();
+    let iso = lower.strip_prefix('p').filter(|rest| {
+        rest.trim_start_matches('t')
+            .starts_with(|c: char| c.is_ascii_digit())
+    });
+    if let Some(iso) = iso {
+        // only a whole iso duration counts, "fix P1 bug" is a title
+        // m is months before T and minutes after it
+        let mut time = false;
+        let mut num = String::new();
+        for c in iso.chars() {
+            let unit = match (c, time) {
+                ('0'..='9', _) => {
+                    num.push(c);
+                    continue;
+                }
+                ('t', false) if num.is_empty() => {
+                    time = true;
+                    continue;
+                }
+                ('y', false) => TimeUnit::Year(0),
+                ('m', false) => TimeUnit::Month(0),
+                ('w', false) => TimeUnit::Week(0),
+                ('d', false) => TimeUnit::Day(0),
+                ('h', true) => TimeUnit::Hour(0),
+                ('m', true) => TimeUnit::Minute(0),
+                ('s', true) => TimeUnit::Second(0),
+                _ => return Ok(None),
+            };
+            if num.is_empty() {
+                return Ok(None);
+            }
+            units.push((std::mem::take(&mut num), unit, String::new()));
+        }
+        if !num.is_empty() {
+            return Ok(None);
+        }
+    } else {
+        if !lower.starts_with(|c: char| c.is_ascii_digit()) {
+            return Ok(None);
+        }
+        // alternating runs of digits and letters
+        let mut runs: Vec<String> = Vec::new();
+        for c in lower.chars() {
+            match runs.last_mut() {
+                Some(run) if run.ends_with(|c: char| c.is_ascii_digit()) == c.is_ascii_digit() => {
+                    run.push(c)
+                }
+                _ => runs.push(c.to_string()),
+            }
+        }
+        for pair in runs.chunks(2) {
+            let [num, name] = pair else {
+                break;
+            };
+            match vocab.lookup(name) {
+                Some(Word::Unit(unit)) => units.push((num.clone(), unit, name.clone())),
+                _ => return Ok(None),
+            }
+        }
+        // 1h30 could be 1h30m or 1:30
+        if runs.len() % 2 == 1 && !units.is_empty() {
+            let smaller = match units.last().map(|(_, unit, _)| unit.rank()) {
+                Some(6) => "mo",
+                Some(5 | 4) => "d",
+                Some(3) => "h",
+                Some(2) => "m",
+                _ => "s",
+            };
+            return Err(Error::parse(str, "last number has no unit")
+                .suggest(format!("did you mean {lower}{smaller}?")));
+        }
+        if units.len() < 2 {
+            return Ok(None);
+        }
+    }
+    let mut parsed: Vec<TimeUnit> = Vec::new();
+    for (num, mut unit, name) in units {
+        *unit = num
+            .parse()
+            .map_err(|_| Error::parse(str, "number is too big"))?;
+        if let Some(last) = parsed.last() {
+            if name == "m" && last.rank() >= TimeUnit::Month(0).rank() {
+                return Err(
+                    Error::parse(str, "m after years or months is ambiguous").suggest(format!(
+                        "write {}mo for months or {}min for minutes",
+                        *unit, *unit
+                    )),
+                );
+            }
+            if unit.rank() == last.rank() {
+                return Err(Error::parse(str, format!("{} given twice", unit.name())));
+            }
+            if unit.rank() > last.rank() {
+                return Err(Error::parse(str, "units must go from largest to smallest")
+                    .suggest("like 1d4h or 1h30m"));
+            }
+        }
+        parsed.push(unit);
+    }
+    parsed.retain(|unit| **unit > 0);
+    match parsed.is_empty() {
+        true => Err(Error::parse(str, "duration is zero")),
+        false => Ok(Some(parsed)),
+    }
+}
+
+// 09:00, 09:00:15.5, 09:00+02:00, 09:00Z: time and offset from utc in seconds
+fn parse_iso_time(str: &str) -> Option<(NaiveTime, Option<i32>)> {
+    let (time, offset) = match str.strip_suffix(['Z', 'z']) {
+        Some(time) => (time, Some(0)),
+        None => match str.rfind(['+', '-']) {
+            Some(i) => {
+                let (time, offset) = str.split_at(i);
+                let sign = if offset.starts_with('-') { -1 } else { 1 };
+                let digits = offset[1..].replace(':', "");
+                let (hours, mins) = digits.split_at(digits.len().min(2));
+                let hours = hours.parse::<i32>().ok()?;
+                let mins = if mins.is_empty() {
+                    0
+                } else {
+                    mins.parse::<i32>().ok()?
+                };
+                if digits.len() > 4 || hours > 23 || mins > 59 {
+                    return None;
+                }
+                (time, Some(sign * (hours * 3600 + mins * 60)))
+            }
+            None => (str, None),
+        },
+    };
+    let time = NaiveTime::parse_from_str(time, "%H:%M:%S%.f")
+        .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M"))
+        .ok()?;
+    Some((time, offset))
+}
+
+// 2026-10-20, 2026-10-20T09:00+02:00, 20.10[.2026] and 10/20[/2026] (20/10 with day_first)
+// dates with year become Arg::Date, without one month and day, None if str isn't a date
+fn parse_date(str: &str, day_first: bool) -> Option<Result<Vec<Arg>, Error>> {
+    let (date_str, time_str) = match str.split_once(['T', 't']) {
+        Some((date, time)) => (date, Some(time)),
+        None => (str, None),
+    };
+    let parts = |sep: char| {
+        let parts = date_str.split(sep).collect::<Vec<_>>();
+        let numeric = parts
+            .iter()
+            .all(|part| (1..=4).contains(&part.len()) && part.bytes().all(|b| b.is_ascii_digit()));
+        (numeric && (2..=3).contains(&parts.len())).then(|| {
+            parts
+                .iter()
+                .map(|part| part.parse::<u32>().unwrap_or(0))
+                .collect::<Vec<_>>()
+        })
+    };
+    let iso = parts('-').filter(|_| date_str.find('-') == Some(4));
+    let is_iso = iso.is_some();
+    let (year, month, day) = match iso {
+        Some(parts) if parts.len() == 3 => (Some(parts[0]), parts[1], parts[2]),
+        _ if time_str.is_some() => return None,
+        Some(_) => return None,
+        None => match (parts('.'), parts('/')) {
+            (Some(parts), _) => (parts.get(2).copied(), parts[1], parts[0]),
+            (_, Some(parts)) => {
+                let (first, second) = (parts[0], parts[1]);
+                // 25/12 can only be day first, 12/25 only month first
+                let day_first = match (first > 12, second > 12) {
+                    (true, false) => true,
+                    (false, true) => false,
+                    _ => day_first,
+                };
+                match day_first {
+                    true => (parts.get(2).copied(), second, first),
+                    false => (parts.get(2).copied(), first, second),
+                }
+            }
+            _ => return None,
+        },
+    };
+    // 20.10.26 is 2026
+    let year = year.map(|year| if year < 100 { year + 2000 } else { year });
+    // 12.50 or 2.0 isn't a date, only an iso one is surely meant as one
+    if !is_iso && NaiveDate::from_ymd_opt(year.unwrap_or(2000) as i32, month, day).is_none() {
+        return None;
+    }
+    if !(1..=12).contains(&month) {
+        return Some(Err(Error::parse(str, "month must be 1-12")));
+    }
+    let Some(year) = year else {
+        return Some(Ok(vec![Arg::Month(month - 1), Arg::Number(day)]));
+    };
+    let year = year as i32;
+    let Some(date) = NaiveDate::from_ymd_opt(year, month, day) else {
+        return Some(Err(invalid_day(year, month - 1, day, true)));
+    };
+    let Some(time_str) = time_str else {
+        return Some(Ok(vec![Arg::Date(date)]));
+    };
+    let Some((time, offset)) = parse_iso_time(time_str) else {
+        return Some(Err(Error::parse(str, "not an ISO 8601 date and time")
+            .suggest("try: 2026-10-20T09:00 or 2026-10-20T09:00+02:00")));
+    };
+    // time in another zone, moved to local
+    let time = match offset.and_then(FixedOffset::east_opt) {
+        Some(offset) => match offset.from_local_datetime(&date.and_time(time)).single() {
+            Some(time) => time.with_timezone(&Local).naive_local(),
+            None => date.and_time(time),
+        },
+        None => date.and_time(time),
+    };
+    Some(Ok(vec![
+        Arg::Date(time.date()),
+        Arg::Time(time.hour(), time.minute(), time.second()),
+    ]))
+}
+
+fn tokenize(args: &[String], config: &Config) -> Result<Vec<Arg>, Error> {
+    let args = config.expand_aliases(args);
+    // word an argument means, ignoring numbers written next to it
+    let word_of = |arg: &String| {
+        let arg_str = num_str_num(arg).1.to_lowercase();
+        config
+            .vocab
+            .lookup(&arg_str)
+            .or_else(|| config.vocab.weekdays(&arg_str).map(Word::WeekDay))
+    };
+    let time_of_day = |arg: &String| parse_time_of_day(arg, config);
+    let is_unit = |arg: Option<&String>| matches!(arg.and_then(word_of), Some(Word::Unit(_)));
+    // in 2 hours, in a week, after tomorrow
+    let is_duration = |first: Option<&String>, second: Option<&String>| {
+        first.is_some_and(|first| first.parse::<u32>().is_ok())
+            || match first.and_then(word_of) {
+                Some(Word::Unit(_) | Word::Tomorrow) => true,
+                Some(Word::Number(_) | Word::Article) => is_unit(second),
+                _ => false,
+            }
+    };
+    // commands stop reading arguments, explain before or after them still counts
+    let explain = args
+        .iter()
+        .skip(1)
+        .any(|arg| word_of(arg) == Some(Word::Explain));
+    let command = |tok| match explain {
+        true => vec![Arg::Explain, tok],
+        false => vec![tok],
+    };
     let mut arg_toks = Vec::new();
-    for arg in args.iter().skip(1) {
+    // positions of day part times and words they came from
+    let mut day_parts = Vec::new();
+    let mut args = args.iter().skip(1).peekable();
+    while let Some(arg) = args.next() {
         let arg = arg.as_str();
+        let is_rrule = ["rrule:", "freq="]
+            .iter()
+            .any(|prefix| arg.to_lowercase().starts_with(prefix));
         let arg_tok: Arg = if let Ok(num) = arg.parse() {
             Arg::Number(num)
+        } else if let Some(date_toks) = parse_date(arg, config.day_first).filter(|_| {
+            // 3.2 or 1/2 right after title text belongs to it, "read chapter 3.2"
+            !(arg.contains(['.', '/']) && matches!(arg_toks.last(), Some(Arg::Title(_))))
+        }) {
+            let mut date_toks = date_toks?;
+            let last = date_toks.pop().unwrap_or(Arg::Title(arg.to_owned()));
+            arg_toks.extend(date_toks);
+            last
+        } else if let Some(day) = ordinal(arg).filter(|_| {
+            // 28th feb, feb 1st, otherwise part of title (3rd floor)
+            matches!(arg_toks.last(), Some(Arg::Month(_)))
+                || matches!(args.peek().copied().and_then(word_of), Some(Word::Month(_)))
+        }) {
+            Arg::Number(day)
+        } else if let Some(mut units) = parse_duration(arg, &config.vocab)? {
+            let last = units
+                .pop()
+                .map_or(Arg::Title(arg.to_owned()), Arg::TimeUnit);
+            arg_toks.extend(units.into_iter().map(Arg::TimeUnit));
+            last
+        } else if is_rrule {
+            match arg.parse() {
+                Ok(rrule) => Arg::RRule(rrule),
+                Err(e) => {
+                    return Err(Error::parse(arg, e)
+                        .suggest("try: remind 9:00 \"FREQ=MONTHLY;BYDAY=2TU\" \"pay rent\""))
+                }
+            }
         } else {
             let (arg_num1, arg_str, arg_num2) = num_str_num(arg);
             let num = arg_num1.max(arg_num2);
-            match arg_str.to_lowercase().as_str() {
-                "zero" => Arg::Number(0),
-                "one" => Arg::Number(1),
-                "two" => Arg::Number(2),
-                "three" => Arg::Number(3),
-                "four" => Arg::Number(4),
-                "five" => Arg::Number(5),
-                "six" => Arg::Number(6),
-                "seven" => Arg::Number(7),
-                "eight" => Arg::Number(8),
-                "nine" => Arg::Number(9),
-                "ten" => Arg::Number(10),
-                "next" | "after" => Arg::Next(num),
-                "rep" | "repe" | "repea" | "repeat" | "rp" | "times" => Arg::Repeat(num),
-                "repeating" | "repetetive" | "every" | "infinite" | "series" | "recurring"
-                | "loop" | "looping" | "cyclic" | "ongoing" => Arg::Repeat(0),
-                "twice" => Arg::Repeat(2),
-                "x" if num > 0 => Arg::Repeat(num),
-                "r" | "re" | "rem" | "remo" | "remov" | "remove" | "rm" | "rmv" | "de" | "del"
-                | "dele" | "delet" | "delete" | "dl" | "dlt" | "erase" | "forget" | "forgt"
-                | "frgt" => Arg::Remove,
-                "hourly" => {
-                    arg_toks.push(Arg::Repeat(0));
-                    Arg::TimeUnit(TimeUnit::Hour(num.max(1)))
-                }
-                "daily" => {
-                    arg_toks.push(Arg::Repeat(0));
-                    Arg::TimeUnit(TimeUnit::Day(num.max(1)))
-                }
-                "weekly" => {
-                    arg_toks.push(Arg::Repeat(0));
-                    Arg::TimeUnit(TimeUnit::Week(num.max(1)))
-                }
-                "monthly" => {
-                    arg_toks.push(Arg::Repeat(0));
-                    Arg::TimeUnit(TimeUnit::Month(num.max(1)))
+            let arg_str = arg_str.to_lowercase();
+            let word = config
+                .vocab
+                .lookup(&arg_str)
+                .or_else(|| config.vocab.weekdays(&arg_str).map(Word::WeekDay));
+            // some commands are common words, only commands before anything else
+            let first = arg_toks.iter().all(|tok| *tok == Arg::Explain);
+            match word {
+                Some(Word::Number(n)) => Arg::Number(n),
+                // lone h is help, 3h is hours
+                Some(Word::Unit(_)) if arg_str == "h" && num == 0 => return Ok(command(Arg::Help)),
+                Some(Word::Unit(mut unit)) => {
+                    *unit = num;
+                    Arg::TimeUnit(unit)
                 }
-                "yearly" | "annual" | "annually" | "anual" | "anually" => {
+                Some(Word::Every(mut unit)) => {
                     arg_toks.push(Arg::Repeat(0));
-                    Arg::TimeUnit(TimeUnit::Year(num.max(1)))
-                }
-                "weekend" | "weeken" | "weeke" | "weeknd" | "wknd" | "wkd" | "break" | "brk"
-                | "holiday" | "rest" | "week-end" | "sun,sat" | "sat,sun" | "sa,su" | "su,sa"
-                | "sn,st" | "st,sn" | "sun|sat" | "sat|sun" | "sa|su" | "su|sa" | "sn|st"
-                | "st|sn" | "sun+sat" | "sat+sun" | "sa+su" | "su+sa" | "sn+st" | "st+sn"
-                | "sun-sat" | "sat-sun" | "sa-su" | "su-sa" | "sn-st" | "st-sn" | "sun_sat"
-                | "sat_sun" | "sa_su" | "su_sa" | "sn_st" | "st_sn" => {
-                    Arg::WeekDay(SUNDAY | SATURDAY)
-                }
-                "work" | "wrk" | "business" | "biz" | "busy" | "workweek" | "work-week" => {
-                    Arg::WeekDay(MONDAY | TUESDAY | WEDNESDAY | THURSDAY | FRIDAY)
-                }
-                "l" | "li" | "lis" | "list" | "ls" | "reminders" | "all" | "see" | "everything" => {
-                    return vec![Arg::List]
+                    *unit = num.max(1);
+                    Arg::TimeUnit(unit)
                 }
-                "clear" | "clean" | "cls" | "clr" | "remove-all" | "rm-all" | "del-all"
-                | "delete-all" | "erase-all" | "rmv-all" | "dlt-all" => return vec![Arg::Clear],
-                "h" | "help" | "hlp" if num == 0 => return vec![Arg::Help],
-                "s" | "se" | "sec" | "seco" | "secon" | "second" | "seconds" | "secs" | "sc"
-                | "scnd" | "scnds" | "secndo" | "sencod" | "secodn" | "secnod" => {
-                    Arg::TimeUnit(TimeUnit::Second(num))
+                Some(Word::Tomorrow) => Arg::TimeUnit(TimeUnit::Day(1)),
+                Some(Word::DayPart(time)) => {
+                    day_parts.push((arg_toks.len(), arg.to_owned()));
+                    Arg::Time(time.hour(), time.minute(), time.second())
                 }
-                "m" | "mi" | "min" | "minu" | "minut" | "minute" | "minutes" | "mins" | "mn"
-                | "mnt" | "mnts" | "mintue" | "minteu" | "mitneu" | "mns" => {
-                    Arg::TimeUnit(TimeUnit::Minute(num))
-                }
-                "h" | "ho" | "hou" | "hour" | "hr" | "hrs" | "hours" | "hs" | "horus" => {
-                    Arg::TimeUnit(TimeUnit::Hour(num))
+                Some(Word::WeekDay(days)) => Arg::WeekDay(days),
+                // 28 feb is feb 28, unless a day follows it (3 feb 28)
+                Some(Word::Month(month)) => match arg_toks.last() {
+                    Some(&Arg::Number(day))
+                        if (1..=31).contains(&day)
+                            && args
+                                .peek()
+                                .and_then(|next| next.parse().ok().or_else(|| ordinal(next)))
+                                .is_none_or(|next: u32| next > 31) =>
+                    {
+                        arg_toks.pop();
+                        arg_toks.push(Arg::Month(month));
+                        Arg::Number(day)
+                    }
+                    _ => Arg::Month(month),
+                },
+                Some(Word::Repeat) => Arg::Repeat(num),
+                // number after every is the step, not repeat count
+                Some(Word::RepeatTimes(0)) if num == 0 => {
+                    let mut ahead = args.clone();
+                    let step = ahead.next().and_then(|arg| step_count(arg, &config.vocab));
+                    match (step, ahead.next().and_then(word_of)) {
+                        (Some(step), Some(Word::Unit(mut unit))) => {
+                            args.nth(1);
+                            arg_toks.push(Arg::Repeat(0));
+                            *unit = step.max(1);
+                            Arg::TimeUnit(unit)
+                        }
+                        _ => Arg::Repeat(0),
+                    }
                 }
-                "d" | "da" | "day" | "days" | "ds" => Arg::TimeUnit(TimeUnit::Day(num)),
-                "tomorrow" | "tomorow" | "tommorrow" | "tommorow" => {
-                    Arg::TimeUnit(TimeUnit::Day(1))
+                Some(Word::RepeatTimes(times)) => Arg::Repeat(times),
+                Some(Word::Times) if num > 0 => Arg::Repeat(num),
+                Some(Word::Next) => Arg::Next(num),
+                // in 2 hours, in a week, after tomorrow, otherwise part of title
+                Some(Word::After) if is_duration(args.clone().next(), args.clone().nth(1)) => {
+                    continue
                 }
-                "w" | "we" | "wee" | "week" | "weeks" | "wk" | "wks" => {
-                    Arg::TimeUnit(TimeUnit::Week(num))
+                Some(Word::This)
+                    if matches!(
+                        args.peek().copied().and_then(word_of),
+                        Some(Word::WeekDay(_) | Word::DayPart(_))
+                    ) =>
+                {
+                    continue
                 }
-                "mo" | "mont" | "month" | "months" | "mnth" | "mnths" => {
-                    Arg::TimeUnit(TimeUnit::Month(num))
+                // between 9am and 5pm, otherwise part of title (between us)
+                Some(Word::Between) if args.peek().and_then(|arg| time_of_day(arg)).is_some() => {
+                    let start = args.next().and_then(&time_of_day);
+                    let joined = args.peek().is_some_and(|arg| {
+                        matches!(
+                            arg.to_lowercase().as_str(),
+                            "and" | "to" | "until" | "till" | "-"
+                        )
+                    });
+                    let end = args.nth(1).filter(|_| joined);
+                    let window = start.zip(end.and_then(&time_of_day));
+                    match window {
+                        Some((start, end)) if start != end => Arg::Window(start, end),
+                        Some(_) => {
+                            return Err(Error::parse(
+                                arg,
+                                "window starts and ends at the same time",
+                            ))
+                        }
+                        None => {
+                            return Err(Error::parse(
+                                arg,
+                                "window needs a start and end time of day",
+                            )
+                            .suggest(
+                                "try: remind every 30 minutes between 9am and 5pm \"stretch\"",
+                            ))
+                        }
+                    }
                 }
-                "y" | "ye" | "yea" | "year" | "years" | "yr" | "yrs" | "ys" => {
-                    Arg::TimeUnit(TimeUnit::Year(num))
+                Some(Word::Article) if is_unit(args.peek().copied()) => continue,
+                Some(Word::Skip) => Arg::Skip(num),
+                Some(Word::Remove) => Arg::Remove,
+                Some(Word::List) => return Ok(command(Arg::List)),
+                Some(Word::Clear) => return Ok(command(Arg::Clear)),
+                Some(Word::Help) if num == 0 => return Ok(command(Arg::Help)),
+                Some(Word::Undo) => match args.peek().map(|a| a.to_lowercase()) {
+                    Some(next) if matches!(next.as_str(), "fire" | "fired" | "notification") => {
+                        args.next();
+                        Arg::UnFire
+                    }
+                    _ => Arg::Undo,
+                },
+                Some(Word::UnFire) => Arg::UnFire,
+                Some(Word::Redo) => Arg::Redo,
+                // "remind 5m log hours" is a title
+                Some(Word::History) if first => Arg::History,
+                Some(Word::CatchUp) => match args.peek().and_then(|policy| policy.parse().ok()) {
+                    Some(catch_up) => {
+                        args.next();
+                        Arg::CatchUp(catch_up)
+                    }
+                    None => Arg::Title(arg.to_owned()),
+                },
+                // "remind 30m missed call back" is a title
+                Some(Word::Missed) if first => return Ok(command(Arg::Missed)),
+                Some(Word::Explain) => Arg::Explain,
+                // only as first argument, "remind 3pm agenda review" is a title
+                Some(Word::Agenda) if arg_toks.is_empty() => {
+                    let span = match args.next() {
+                        Some(span) => span.parse().map_err(|e: String| Error::parse(span, e))?,
+                        None => Span::default(),
+                    };
+                    return Ok(command(Arg::Agenda(span)));
                 }
-                "su" | "sun" | "sund" | "sunda" | "sunday" | "sn" | "snd" => Arg::WeekDay(SUNDAY),
-                "mon" | "mond" | "monda" | "monday" | "md" | "mnd" => Arg::WeekDay(MONDAY),
-                "tu" | "tue" | "tues" | "tuesd" | "tuesda" | "tuesday" | "tsd" => {
-                    Arg::WeekDay(TUESDAY)
+                // only as first argument, "remind 5m edit video" is a title
+                Some(Word::Edit) if first => match args.next() {
+                    Some(query) => Arg::Edit(query.to_owned()),
+                    None => Arg::Title(arg.to_owned()),
+                },
+                // "remind 5m check email" is a title
+                Some(Word::Doctor) if first => {
+                    let fix = args.peek().is_some_and(|arg| {
+                        matches!(arg.to_lowercase().as_str(), "fix" | "repair" | "--fix")
+                    });
+                    return Ok(command(Arg::Doctor(fix)));
                 }
-                "wed" | "wedn" | "wedne" | "wednes" | "wednesd" | "wednesda" | "wednesday"
-                | "wd" | "wednsd" | "wdnsd" => Arg::WeekDay(WEDNESDAY),
-                "th" | "thu" | "thur" | "thurs" | "thursd" | "thursda" | "thursday" | "thrsd" => {
-                    Arg::WeekDay(THURSDAY)
+                // cron "0 9 * * 1-5"
+                Some(Word::Cron)
+                    if args
+                        .peek()
+                        .is_some_and(|expr| expr.contains(' ') || expr.starts_with('@')) =>
+                {
+                    let expr = args.next().unwrap();
+                    match expr.parse() {
+                        Ok(cron) => Arg::Cron(cron),
+                        Err(e) => {
+                            return Err(Error::parse(expr, e)
+                                .suggest("try: remind cron \"0 9 * * 1-5\" \"standup\""))
+                        }
+                    }
                 }
-                "fr" | "fri" | "frid" | "frida" | "friday" | "fd" | "frd" => Arg::WeekDay(FRIDAY),
-                "sa" | "sat" | "satu" | "satur" | "saturd" | "saturda" | "saturday" | "st"
-                | "strd" => Arg::WeekDay(SATURDAY),
-                "january" | "janu" | "jan" => Arg::Month(0),
-                "february" | "febr" | "feb" => Arg::Month(1),
-                "march" | "marc" | "mar" => Arg::Month(2),
-                "april" | "aprl" | "apr" => Arg::Month(3),
-                "may" => Arg::Month(4),
-                "june" | "jun" => Arg::Month(5),
-                "july" | "jul" => Arg::Month(6),
-                "august" | "augu" | "aug" => Arg::Month(7),
-                "september" | "sept" | "sep" => Arg::Month(8),
-                "october" | "octo" | "oct" => Arg::Month(9),
-                "november" | "nove" | "nov" => Arg::Month(10),
-                "december" | "dece" | "dec" => Arg::Month(11),
-                "skip" | "sk" | "skp" | "snooze" | "snz" | "skip-next" | "sk-next" | "skp-next"
-                | "snooze-next" | "snz-next" => Arg::Skip(num),
-                "undo" | "goback" | "go-back" => Arg::Undo,
                 _ => {
                     let arg_lower = arg.to_lowercase();
                     let mut arg_str = arg_lower.as_str();
@@ -268,11 +681,20 @@ fn tokenize(args: &[String]) -> Vec<Arg> {
                             it.next().and_then(|m| m.parse().ok()).unwrap_or(E),
                             it.next().and_then(|s| s.parse().ok()).unwrap_or(E),
                         );
-                        if pm && hour != E {
-                            hour = (hour + 12) % 24;
+                        if (pm || am) && hour != E && !(1..=12).contains(&hour) {
+                            let error = Error::parse(arg, "hour must be 1-12 with am/pm");
+                            return Err(match hour {
+                                0 => error.suggest("did you mean 12am?"),
+                                13..=23 => error.suggest(format!("did you mean {hour}:00?")),
+                                _ => error,
+                            });
+                        }
+                        // 12am is midnight, 12pm is noon
+                        if (pm || am) && hour != E {
+                            hour = hour % 12 + if pm { 12 } else { 0 };
                         }
                         let now = Local::now();
-                        match (hour, min, sec) {
+                        let time = match (hour, min, sec) {
                             (E, E, E) => Arg::Title(arg.to_owned()),
                             (h, E, E) => Arg::Time(h, 0, 0),
                             (E, m, E) => Arg::Time(now.hour(), m, 0),
@@ -280,6 +702,14 @@ fn tokenize(args: &[String]) -> Vec<Arg> {
                             (E, m, s) => Arg::Time(now.hour(), m, s),
                             (h, m, E) => Arg::Time(h, m, 0),
                             (h, m, s) => Arg::Time(h, m, s),
+                        };
+                        match time {
+                            Arg::Time(h, m, s) if h > 23 || m > 59 || s > 59 => {
+                                let (h, m) = (h.min(23), m.min(59));
+                                return Err(Error::parse(arg, "not a time of day")
+                                    .suggest(format!("did you mean {h}:{m:02}?")));
+                            }
+                            time => time,
                         }
                     } else {
                         Arg::Title(arg.to_owned())
@@ -290,7 +720,32 @@ fn tokenize(args: &[String]) -> Vec<Arg> {
         arg_toks.push(arg_tok);
     }
 
-    arg_toks.into_iter().fold(Vec::new(), |mut acc, tok| {
+    // day part is time of day when nothing else gives a time, or next to a date (friday evening)
+    // otherwise it's part of title, "5m morning run"
+    let is_day_part = |i: usize| day_parts.iter().any(|(j, _)| *j == i);
+    let timed = arg_toks.iter().enumerate().any(|(i, tok)| {
+        !is_day_part(i) && matches!(tok, Arg::Time(..) | Arg::TimeUnit(_) | Arg::Window(..))
+    });
+    let dated = |i: Option<usize>| match i.and_then(|i| arg_toks.get(i)) {
+        Some(Arg::WeekDay(_) | Arg::Date(_) | Arg::Month(_) | Arg::TimeUnit(TimeUnit::Day(_))) => {
+            true
+        }
+        // feb 28 evening
+        Some(Arg::Number(_)) => i
+            .and_then(|i| i.checked_sub(1))
+            .is_some_and(|i| matches!(arg_toks[i], Arg::Month(_))),
+        _ => false,
+    };
+    let titles = day_parts
+        .iter()
+        .filter(|(i, _)| timed && !dated(i.checked_sub(1)) && !dated(Some(i + 1)))
+        .cloned()
+        .collect::<Vec<_>>();
+    for (i, word) in titles {
+        arg_toks[i] = Arg::Title(word);
+    }
+
+    Ok(arg_toks.into_iter().fold(Vec::new(), |mut acc, tok| {
         if let Some(last) = acc.last_mut() {
             match (last, &tok) {
                 (Arg::Title(last), Arg::Title(tok)) => {
@@ -303,35 +758,93 @@ fn tokenize(args: &[String]) -> Vec<Arg> {
             acc.push(tok);
         }
         acc
+    }))
+}
+
+// (previous, current, next) token triples, Arg::Help stands in past either end
+fn windows(tokens: &[Arg]) -> impl Iterator<Item = (Arg, Arg, Arg)> + '_ {
+    tokens.iter().enumerate().map(|(i, tok)| {
+        let prev_tok = if i > 0 {
+            tokens[i - 1].clone()
+        } else {
+            Arg::Help
+        };
+        let next_tok = tokens.get(i + 1).cloned().unwrap_or(Arg::Help);
+        (prev_tok, tok.clone(), next_tok)
     })
 }
 
-fn main() {
-    let args: Vec<String> = std::env::args().collect();
+// reminder described by tokens, along with which of its fields were mentioned
+struct Parsed {
+    reminder: Reminder,
+    title: bool,
+    // time units, dates, weekdays, rrule or cron
+    schedule: bool,
+    // time of day, when given without anything else in schedule
+    time: Option<NaiveTime>,
+    repeats: bool,
+    catch_up: bool,
+    window: bool,
+}
 
-    let dir = directories::ProjectDirs::from("", "", "Remind")
-        .unwrap_or_else(|| panic!("saved reminders folder does not exist"));
-    let mut dir = dir.data_local_dir().to_owned();
-    if dir.ends_with("data") {
-        dir = dir.parent().unwrap().to_owned();
+impl Parsed {
+    // patches only mentioned fields of an existing reminder
+    fn apply(self, reminder: &mut Reminder) -> Result<(), Error> {
+        let new = self.reminder;
+        if self.title {
+            reminder.title = new.title;
+        }
+        if self.schedule {
+            reminder.interval = new.interval;
+            reminder.end_time = new.end_time;
+            reminder.weekdays = new.weekdays;
+            reminder.rrule = new.rrule;
+            reminder.cron = new.cron;
+        } else if let Some(time) = self.time {
+            reminder.end_time = local_time(reminder.end_time.date_naive().and_time(time));
+            while reminder.end_time <= Local::now() {
+                reminder.end_time += Duration::days(1);
+            }
+        }
+        if self.repeats {
+            reminder.repeats = new.repeats;
+        }
+        if self.catch_up {
+            reminder.catch_up = new.catch_up;
+        }
+        if self.window {
+            reminder.window = new.window;
+        }
+        reminder.update();
+        check_window(reminder)
     }
-    let file = dir.join("reminders.txt");
-    let history_dir = dir.join("history");
-    std::fs::create_dir_all(&dir).unwrap_or_default();
-    std::fs::create_dir(&history_dir).unwrap_or_default();
+}
 
-    let mut reminder_file = ReminderFile::new(&file, &history_dir);
-    if args.len() <= 1 {
-        let file = file.to_str().unwrap_or_default();
-        println!("reminders at: {file}");
-        reminder_file.wait_next();
-        return;
-    }
+// short month name, 0 based: 1 -> feb
+fn month_name(month: u32) -> String {
+    NaiveDate::from_ymd_opt(2000, month + 1, 1)
+        .map(|date| date.format("%b").to_string().to_lowercase())
+        .unwrap_or_default()
+}
 
-    reminder_file.load();
+// day that doesn't exist in month, suggests closest one that does
+fn invalid_day(year: i32, month: u32, day: u32, show_year: bool) -> Error {
+    let last_day = (28..=31)
+        .rev()
+        .find(|day| NaiveDate::from_ymd_opt(year, month + 1, *day).is_some())
+        .unwrap_or(28);
+    let month = month_name(month);
+    let day_suggestion = day.clamp(1, last_day);
+    let suggestion = match show_year {
+        true => format!("{month} {day_suggestion} {year}"),
+        false => format!("{month} {day_suggestion}"),
+    };
+    Error::schedule(format!("{month} {day} {year} does not exist"))
+        .suggest(format!("did you mean {suggestion}?"))
+}
 
-    // Tokenize arguments
-    let tokens = tokenize(&args);
+// error if tokens describe time that doesn't exist, or already passed and won't come again
+fn parse_reminder(tokens: &[Arg], config: &Config) -> Result<Parsed, Error> {
     let mut title = String::new();
     let mut weekdays: u8 = 0;
     let mut repeats = None;
@@ -340,133 +853,135 @@ fn main() {
     let mut interval = Interval::default();
     let mut default_interval = Interval::default();
     let mut next = 0;
-    for (i, tok) in tokens.iter().enumerate() {
-        let mut add_time_unit = |unit: TimeUnit| {
-            match unit {
-                TimeUnit::Second(sec) => (
-                    interval.secs += sec,
-                    end_time += Duration::seconds(sec as i64),
-                ),
-                TimeUnit::Minute(min) => (
-                    interval.mins += min,
-                    end_time += Duration::minutes(min as i64),
-                ),
-                TimeUnit::Hour(hour) => (
-                    interval.hours += hour,
-                    end_time += Duration::hours(hour as i64),
-                ),
-                TimeUnit::Day(day) => {
-                    (interval.days += day, end_time += Duration::days(day as i64))
-                }
+    let mut catch_up = None;
+    let mut rrule: Option<RRule> = None;
+    let mut cron: Option<Cron> = None;
+    let mut window = None;
+    let mut schedule = false;
+    let mut time = None;
+    // date given without time of day, gets default time from config
+    let mut dated = false;
+    let is_year = |year: u32| year as i32 >= now.year() && year < 2200;
+    let explicit_year = tokens.iter().find_map(|tok| match tok {
+        Arg::Number(year) if is_year(*year) => Some(*year as i32),
+        _ => None,
+    });
+    let too_far = || Error::schedule("that is too far in the future");
+    for (prev_tok, tok, next_tok) in windows(tokens) {
+        let mut add_time_unit = |unit: TimeUnit| -> Result<(), Error> {
+            // huge counts overflow the interval or the end time, both are too far
+            let (count, units, delta) = match unit {
+                TimeUnit::Second(sec) => (sec, &mut interval.secs, Duration::seconds(sec as i64)),
+                TimeUnit::Minute(min) => (min, &mut interval.mins, Duration::minutes(min as i64)),
+                TimeUnit::Hour(hour) => (hour, &mut interval.hours, Duration::hours(hour as i64)),
+                TimeUnit::Day(day) => (day, &mut interval.days, Duration::days(day as i64)),
                 TimeUnit::Week(week) => (
-                    interval.days += 7 * week,
-                    end_time += Duration::days(7 * week as i64),
-                ),
-                TimeUnit::Month(month) => (
-                    interval.months += month,
-                    end_time = end_time.checked_add_months(Months::new(month)).unwrap(),
-                ),
-                TimeUnit::Year(year) => (
-                    interval.years += year,
-                    end_time = end_time.with_year(end_time.year() + year as i32).unwrap(),
+                    week.checked_mul(7).ok_or_else(too_far)?,
+                    &mut interval.days,
+                    Duration::weeks(week as i64),
                 ),
+                TimeUnit::Month(month) => {
+                    interval.months = interval.months.checked_add(month).ok_or_else(too_far)?;
+                    end_time = end_time
+                        .checked_add_months(Months::new(month))
+                        .ok_or_else(too_far)?;
+                    return Ok(());
+                }
+                // feb 29 + 1 year is feb 28
+                TimeUnit::Year(year) => {
+                    interval.years = interval.years.checked_add(year).ok_or_else(too_far)?;
+                    end_time = year
+                        .checked_mul(12)
+                        .and_then(|months| end_time.checked_add_months(Months::new(months)))
+                        .ok_or_else(too_far)?;
+                    return Ok(());
+                }
             };
+            *units = units.checked_add(count).ok_or_else(too_far)?;
+            end_time = end_time.checked_add_signed(delta).ok_or_else(too_far)?;
+            Ok(())
         };
-        let prev_tok = if i > 0 {
-            tokens[i - 1].clone()
-        } else {
-            Arg::Help
-        };
-        let next_tok = if i < tokens.len() - 1 {
-            tokens[i + 1].clone()
-        } else {
-            Arg::Help
-        };
-        match (prev_tok, tok.clone(), next_tok) {
-            (_, Arg::List, _) => {
-                reminder_file.list();
-                return;
-            }
-            (_, Arg::Help, _) => {
-                print_help();
-                return;
-            }
-            (_, Arg::Undo, _) => {
-                reminder_file.undo();
-                return;
-            }
-            (_, Arg::Clear, _) => {
-                reminder_file.save_history();
-                reminder_file.clear();
-                return;
-            }
-            (_, Arg::Remove, Arg::Title(titl)) | (Arg::Title(titl), Arg::Remove, _) => {
-                reminder_file.save_history();
-                reminder_file.remove(&titl);
-                return;
-            }
-            (_, Arg::Remove, _) => {
-                reminder_file.remove_last();
-                return;
-            }
-            // skip 3 "reminder" | skip "reminder" 3 | "reminder" skip 3
-            // skip "reminder" | "reminder" skip | skip3 "reminder" | "reminder" skip3
-            (Arg::Skip(0), Arg::Number(skips), Arg::Title(title))
-            | (Arg::Skip(0), Arg::Title(title), Arg::Number(skips))
-            | (Arg::Title(title), Arg::Skip(0), Arg::Number(skips))
-            | (_, Arg::Skip(skips), Arg::Title(title))
-            | (_, Arg::Title(title), Arg::Skip(skips)) => {
-                reminder_file.save_history();
-                reminder_file.skip(&title, skips.max(1));
-                return;
-            }
-            // skip | skip 3 | skip3
-            (_, Arg::Skip(0), Arg::Number(skips)) | (_, Arg::Skip(skips), _) => {
-                reminder_file.save_history();
-                reminder_file.skip_next(skips.max(1));
-                return;
-            }
+        match (prev_tok, tok, next_tok) {
             (_, Arg::Next(0), Arg::Number(num)) | (_, Arg::Next(num), _) => {
                 next = num.max(1);
+                schedule = true;
             }
             (_, Arg::Title(titl), _) => title = titl,
+            (_, Arg::CatchUp(policy), _) => catch_up = Some(policy),
+            (_, Arg::Window(start, end), _) => window = Some((start, end)),
+            (_, Arg::RRule(rule), _) => {
+                rrule = Some(rule);
+                schedule = true;
+            }
+            (_, Arg::Cron(expr), _) => {
+                cron = Some(expr);
+                schedule = true;
+            }
             (_, Arg::Repeat(0), Arg::Number(reps)) if repeats.is_none() => repeats = Some(reps),
             (_, Arg::Repeat(reps), _) => repeats = Some(reps),
+            (_, Arg::Date(date), _) => {
+                end_time = local_time(date.and_time(end_time.time()));
+                default_interval.years = u32::MAX;
+                schedule = true;
+                dated = true;
+            }
             (_, Arg::Month(month), Arg::Number(day)) => {
-                end_time = end_time.with_month0(month).unwrap();
-                end_time = end_time.with_day(day).unwrap();
+                let year = explicit_year.unwrap_or(end_time.year());
+                let date = NaiveDate::from_ymd_opt(year, month + 1, day)
+                    .ok_or_else(|| invalid_day(year, month, day, explicit_year.is_some()))?;
+                end_time = local_time(date.and_time(end_time.time()));
                 default_interval.years = 1;
+                schedule = true;
+                dated = true;
             }
             (_, Arg::TimeUnit(mut unit), Arg::Number(time))
             | (Arg::Number(time), Arg::TimeUnit(mut unit), _) => {
                 *unit = if *unit == 0 { time } else { *unit }.max(1);
-                add_time_unit(unit);
+                add_time_unit(unit)?;
+                schedule = true;
             }
             (_, Arg::TimeUnit(mut unit), _) => {
                 *unit = (*unit).max(1);
-                add_time_unit(unit);
+                add_time_unit(unit)?;
+                schedule = true;
             }
-            (_, Arg::Number(year), _) if year as i32 >= now.year() && year < 2200 => {
-                end_time = end_time.with_year(year as i32).unwrap();
+            (_, Arg::Number(year), _) if is_year(year) => {
+                end_time = end_time
+                    .with_year(year as i32)
+                    .ok_or_else(|| invalid_day(year as i32, 1, 29, true))?;
                 default_interval.years = u32::MAX;
+                schedule = true;
+                dated = true;
             }
             (_, Arg::WeekDay(bits), _) => {
                 weekdays |= bits;
-                default_interval.days = if bits.count_ones() == 1 { 7 } else { 1 }
+                default_interval.days = if bits.count_ones() == 1 { 7 } else { 1 };
+                schedule = true;
+                dated = true;
             }
             (_, Arg::Time(h, m, s), _) => {
-                end_time = end_time
-                    .with_time(NaiveTime::from_hms_opt(h, m, s).unwrap())
-                    .unwrap();
+                let time_of_day = NaiveTime::from_hms_opt(h, m, s).ok_or_else(|| {
+                    Error::parse(&format!("{h}:{m:02}:{s:02}"), "not a time of day")
+                })?;
+                end_time = local_time(end_time.date_naive().and_time(time_of_day));
                 default_interval.days = 1;
+                time = Some(time_of_day);
             }
-            (a, Arg::Month(_), b) => panic!(
-                "invalid month pattern ({a:?} Arg::Month {b:?}), try: remind july 4 \"my reminder\""
-            ),
-            (_, Arg::Number(_), _) => {}
+            (_, Arg::Month(month), _) => {
+                return Err(
+                    Error::parse(&month_name(month), "month needs a day after it").suggest(
+                        format!("try: remind {} 4 \"my reminder\"", month_name(month)),
+                    ),
+                )
+            }
+            _ => {}
         };
     }
 
+    if let Some(default_time) = config.default_time.filter(|_| dated && time.is_none()) {
+        end_time = local_time(end_time.date_naive().and_time(default_time));
+    }
+
     if interval.is_zero() {
         if default_interval.years == u32::MAX {
             repeats = Some(1);
@@ -481,106 +996,2023 @@ fn main() {
         }
     }
 
+    // coming weekday, today if its time is still ahead, next friday is a week after this one
+    if weekdays != 0 && rrule.is_none() && cron.is_none() {
+        let on_weekday = |end_time| {
+            let reminder = Reminder {
+                weekdays,
+                end_time,
+                ..Default::default()
+            };
+            reminder.weekdays_match_end_weekday()
+        };
+        while end_time <= now || !on_weekday(end_time) {
+            end_time += Duration::days(1);
+        }
+        end_time = end_time
+            .checked_add_signed(Duration::weeks(next as i64))
+            .ok_or_else(too_far)?;
+    }
+
     if end_time <= now {
         for _ in 0..=next {
             if default_interval.years == u32::MAX {
-                return;
+                let date = end_time.format("%b %-d %Y").to_string().to_lowercase();
+                return Err(Error::schedule(format!("{date} has already passed")));
             } else if default_interval.years > 0 {
-                end_time = end_time.with_year(now.year() + 1).unwrap();
+                let year = now.year() + 1;
+                end_time = end_time
+                    .with_year(year)
+                    .ok_or_else(|| invalid_day(year, 1, 29, true))?;
             } else if default_interval.months > 0 {
-                end_time = end_time.checked_add_months(Months::new(1)).unwrap();
+                end_time = end_time
+                    .checked_add_months(Months::new(1))
+                    .ok_or_else(too_far)?;
             } else if default_interval.days > 0 {
                 end_time += Duration::days(default_interval.days as i64);
             }
         }
     }
 
-    let mut reminder = Reminder {
+    if let Some(rrule) = &rrule {
+        // first occurrence after now, start time only anchors time of day and interval
+        let start = end_time.naive_local();
+        match rrule.iter(start).find(|time| local_time(*time) > now) {
+            Some(first) => end_time = local_time(first),
+            None => {
+                return Err(Error::schedule(format!(
+                    "rrule \"{rrule}\" has no upcoming occurrences"
+                )))
+            }
+        }
+        // remaining count is tracked by repeats
+        repeats = repeats.or(rrule.count).or(Some(0));
+    } else if let Some(cron) = &cron {
+        match cron.next_after(now.naive_local()) {
+            Some(first) => end_time = local_time(first),
+            None => return Err(Error::schedule(format!("cron \"{cron}\" never fires"))),
+        }
+        repeats = repeats.or(Some(0));
+    }
+
+    let reminder = Reminder {
+        id: String::new(),
         title,
         interval,
         end_time,
         repeats: repeats.unwrap_or(1),
         skips: 0,
         weekdays,
+        catch_up,
+        rrule,
+        cron,
+        window,
     };
-    reminder.update();
-    reminder_file.save_history();
-    reminder_file.append(&reminder);
+    // window on its own is only an edit, checked once applied to the reminder it's set on
+    if schedule || !reminder.title.is_empty() {
+        check_window(&reminder)?;
+    }
+    Ok(Parsed {
+        title: !reminder.title.is_empty(),
+        repeats: repeats.is_some(),
+        catch_up: reminder.catch_up.is_some(),
+        window: reminder.window.is_some(),
+        schedule,
+        time,
+        reminder,
+    })
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_num_str_num() {
-        assert_eq!(num_str_num("32week4"), (32, "week".to_string(), 4));
-        assert_eq!(num_str_num("10days"), (10, "days".to_string(), 0));
-        assert_eq!(num_str_num("months5"), (0, "months".to_string(), 5));
-        assert_eq!(num_str_num("year"), (0, "year".to_string(), 0));
+fn check_window(reminder: &Reminder) -> Result<(), Error> {
+    match reminder.window_error() {
+        Some(message) => Err(Error::parse("between", message)
+            .suggest("try: remind every 30 minutes between 9am and 5pm \"stretch\"")),
+        None => Ok(()),
     }
+}
 
-    fn to_args(str: &[&str]) -> Vec<String> {
-        str.iter().map(|s| s.to_string()).collect()
+// prints tokens, reminder they make (or edit would make) and when it fires, saves nothing
+fn explain(tokens: &[Arg], config: &Config, reminder_file: &ReminderFile) -> Result<(), Error> {
+    // dry run never writes, so commands that change or only show reminders aren't explained
+    let command = tokens.iter().find_map(|tok| match tok {
+        Arg::Remove => Some("remove"),
+        Arg::Skip(_) => Some("skip"),
+        Arg::Missed => Some("missed"),
+        Arg::Doctor(_) => Some("doctor"),
+        Arg::Agenda(_) => Some("agenda"),
+        Arg::Undo | Arg::UnFire => Some("undo"),
+        Arg::Redo => Some("redo"),
+        Arg::History => Some("history"),
+        Arg::Clear => Some("clear"),
+        Arg::List => Some("list"),
+        Arg::Help => Some("help"),
+        _ => None,
+    });
+    if let Some(command) = command {
+        return Err(Error::parse(
+            command,
+            "explain only describes adding or editing reminders",
+        ));
+    }
+    let token_strs = tokens
+        .iter()
+        .map(|tok| match tok {
+            Arg::WeekDay(bits) => {
+                let days = Reminder {
+                    weekdays: *bits,
+                    ..Default::default()
+                };
+                format!("WeekDay({})", days.weekdays_to_str())
+            }
+            Arg::Month(month) => format!("Month({})", month_name(*month)),
+            tok => format!("{tok:?}"),
+        })
+        .collect::<Vec<_>>();
+    println!("tokens: {}", token_strs.join(" "));
+    let reminder = match tokens.first() {
+        Some(Arg::Edit(query)) => {
+            let parsed = parse_reminder(&tokens[1..], config)?;
+            let Some(before) = reminder_file.get(query) else {
+                println!("no reminders with id or title \"{query}\" found");
+                return Ok(());
+            };
+            let mut after = before.clone();
+            parsed.apply(&mut after)?;
+            println!("before: {before}");
+            after
+        }
+        _ => {
+            let mut reminder = parse_reminder(tokens, config)?.reminder;
+            reminder.update();
+            reminder
+        }
+    };
+    println!("reminder: {reminder}");
+    for field in reminder.serialize().trim_end().split('\t') {
+        match field.split_once('=') {
+            Some((_, "")) | None => {}
+            Some((key, value)) => println!("  {key}: {value}"),
+        }
     }
+    println!("next {UPCOMING}:");
+    print_occurrences(&reminder, UPCOMING);
+    println!("nothing saved");
+    Ok(())
+}
 
-    #[test]
-    fn test_tokenize() {
-        let args = to_args(&["remind", "3w", "write homework"]);
-        let tokens = tokenize(&args);
-        assert_eq!(
-            tokens,
-            vec![
-                Arg::TimeUnit(TimeUnit::Week(3)),
-                Arg::Title("write homework".to_string())
-            ]
-        );
+// removes --expand [N] from args, Some(N) if it was there
+fn take_expand_arg(args: &mut Vec<String>) -> Result<Option<usize>, Error> {
+    let Some(i) = args
+        .iter()
+        .position(|arg| arg == "--expand" || arg.starts_with("--expand="))
+    else {
+        return Ok(None);
+    };
+    let arg = args.remove(i);
+    let count = match arg.split_once('=') {
+        Some((_, count)) => count.to_owned(),
+        None if args
+            .get(i)
+            .is_some_and(|count| count.parse::<usize>().is_ok()) =>
+        {
+            args.remove(i)
+        }
+        None => return Ok(Some(UPCOMING)),
+    };
+    count
+        .parse()
+        .map(Some)
+        .map_err(|_| Error::parse(&count, "--expand expects a number"))
+}
 
-        let args = to_args(&["remind", "1m", "egg ready", "rep4", "skip", "3"]);
-        let tokens = tokenize(&args);
-        assert_eq!(
-            tokens,
-            [
-                Arg::TimeUnit(TimeUnit::Minute(1)),
-                Arg::Title("egg ready".to_string()),
-                Arg::Repeat(4),
-                Arg::Skip(0),
-                Arg::Number(3)
-            ]
-        );
+// removes --file <path> and -l/--list <name> from args, they can go anywhere
+fn take_location_args(args: &mut Vec<String>) -> Result<(Option<PathBuf>, Option<String>), Error> {
+    let mut file = None;
+    let mut list = None;
+    let mut i = 1;
+    while i < args.len() {
+        let (opt, value) = match args[i].split_once('=') {
+            Some((opt, value)) if opt.starts_with("--") => (opt.to_owned(), Some(value.to_owned())),
+            _ => (args[i].clone(), None),
+        };
+        if !matches!(opt.as_str(), "--file" | "-l" | "--list") {
+            i += 1;
+            continue;
+        }
+        args.remove(i);
+        let value = match value {
+            Some(value) => value,
+            None if i < args.len() => args.remove(i),
+            None => return Err(Error::parse(&opt, "expects a value")),
+        };
+        if opt == "--file" {
+            file = Some(PathBuf::from(value));
+        } else if !value.is_empty()
+            && value
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        {
+            list = Some(value.to_lowercase());
+        } else {
+            return Err(Error::parse(
+                &value,
+                "list name can only use letters, digits, '-' and '_'",
+            ));
+        }
+    }
+    Ok((file, list))
+}
 
-        let args = to_args(&["remind", "july", "4", "pay", "12:30"]);
-        let tokens = tokenize(&args);
-        let now = Local::now();
-        assert_eq!(
-            tokens,
-            vec![
-                Arg::Month(6),
-                Arg::Number(4),
-                Arg::Title("pay".to_string()),
-                Arg::Time(12, 30, now.second())
-            ]
-        );
+fn main() -> std::process::ExitCode {
+    match run() {
+        Ok(()) => std::process::ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::ExitCode::from(e.exit_code())
+        }
     }
+}
 
-    #[test]
-    fn test_print_help() {
-        print_help();
+fn run() -> Result<(), Error> {
+    let mut args: Vec<String> = std::env::args().collect();
+    let (file_arg, list) = take_location_args(&mut args)?;
+    let expand = take_expand_arg(&mut args)?;
+
+    let dir = directories::ProjectDirs::from("", "", "Remind")
+        .ok_or_else(|| Error::storage("~", "no home folder to keep reminders in"))?;
+    let mut dir = dir.data_local_dir().to_owned();
+    if dir.ends_with("data") {
+        dir = dir.parent().map_or(dir.clone(), Path::to_owned);
+    }
+    // named lists are kept next to default reminders file
+    let default_file = file_arg
+        .or(std::env::var_os("REMIND_FILE").map(PathBuf::from))
+        .unwrap_or(dir.join("reminders.txt"));
+    let default_file = std::path::absolute(&default_file).unwrap_or(default_file);
+    let lists_dir = default_file
+        .parent()
+        .map_or(PathBuf::from("lists"), |dir| dir.join("lists"));
+    let file = match &list {
+        Some(name) => lists_dir.join(format!("{name}.txt")),
+        None => default_file.clone(),
+    };
+    if let Some(dir) = file.parent() {
+        std::fs::create_dir_all(dir).unwrap_or_default();
     }
 
-    #[test]
-    fn test_reminder_file() {
-        let mut reminder_file = ReminderFile::new("test_reminders.txt", "test_history");
-        reminder_file.append(&Reminder {
-            title: "Test Reminder".to_string(),
-            interval: Interval::default(),
-            end_time: Local::now(),
+    // config is kept next to default reminders file too, so --file and REMIND_FILE bring their own
+    let config_file = default_file
+        .parent()
+        .map_or(dir.join(Config::FILE_NAME), |dir| {
+            dir.join(Config::FILE_NAME)
+        });
+    let config = Config::load(&config_file).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        Config::default()
+    });
+    // env vars take precedence over config
+    let retention = std::env::var("REMIND_HISTORY_RETENTION")
+        .ok()
+        .and_then(|retention| match retention.parse::<Retention>() {
+            Ok(retention) => Some(retention),
+            Err(e) => {
+                eprintln!("REMIND_HISTORY_RETENTION: {e}");
+                None
+            }
+        })
+        .or(config.history_retention);
+    let catch_up = std::env::var("REMIND_CATCH_UP")
+        .ok()
+        .and_then(|policy| match policy.parse::<CatchUp>() {
+            Ok(catch_up) => Some(catch_up),
+            Err(e) => {
+                eprintln!("REMIND_CATCH_UP: {e}");
+                None
+            }
+        })
+        .or(config.catch_up);
+    let open = |file: &Path| {
+        let mut reminder_file = ReminderFile::new(file);
+        if file.parent() == Some(lists_dir.as_path()) {
+            if let Some(name) = file.file_stem() {
+                reminder_file.set_label(&name.to_string_lossy());
+            }
+        }
+        if let Some(retention) = retention {
+            reminder_file.set_history_retention(retention);
+        }
+        if let Some(catch_up) = catch_up {
+            reminder_file.set_catch_up(catch_up);
+        }
+        if let Some(timeout) = config.notification_timeout {
+            reminder_file.set_notification_timeout(timeout);
+        }
+        reminder_file
+    };
+
+    if args.len() <= 1 {
+        // without -l daemon watches default file and every list
+        let mut files = vec![open(&file)];
+        if list.is_none() {
+            files.extend(daemon::list_files(&lists_dir).iter().map(|path| open(path)));
+        }
+        println!("reminders at: {}", file.display());
+        return daemon::run(files, list.is_none().then_some(lists_dir.clone()), open);
+    }
+
+    // parse before touching reminders file, bad arguments shouldn't wait on lock
+    let tokens = tokenize(&args, &config)?;
+    let mut reminder_file = open(&file);
+    // explain only reads, load would upgrade or repair the file
+    if tokens.contains(&Arg::Explain) {
+        let tokens = tokens
+            .into_iter()
+            .filter(|tok| *tok != Arg::Explain)
+            .collect::<Vec<_>>();
+        reminder_file.load_read_only();
+        return explain(&tokens, &config, &reminder_file);
+    }
+    let _lock = reminder_file.lock()?;
+    reminder_file.load()?;
+    if let Some(Arg::Edit(query)) = tokens.first() {
+        let parsed = parse_reminder(&tokens[1..], &config)?;
+        reminder_file.save_history(Op::Edit);
+        return reminder_file.edit(query, |reminder| parsed.apply(reminder));
+    }
+    // next "reminder" [N], next also schedules when given anything else
+    match tokens.as_slice() {
+        [Arg::Next(0), Arg::Title(query)] => {
+            reminder_file.next(query, UPCOMING);
+            return Ok(());
+        }
+        [Arg::Next(0), Arg::Title(query), Arg::Number(count)]
+        | [Arg::Next(0), Arg::Number(count), Arg::Title(query)]
+        | [Arg::Next(count), Arg::Title(query)] => {
+            reminder_file.next(query, *count as usize);
+            return Ok(());
+        }
+        _ => {}
+    }
+    for (prev_tok, tok, next_tok) in windows(&tokens) {
+        match (prev_tok, tok, next_tok) {
+            (_, Arg::List, _) => {
+                match expand {
+                    Some(count) => reminder_file.list_expanded(count),
+                    None => reminder_file.list(),
+                }
+                return Ok(());
+            }
+            (_, Arg::Agenda(span), _) => {
+                reminder_file.agenda(span);
+                return Ok(());
+            }
+            (_, Arg::Help, _) => {
+                print_help(&config.vocab);
+                return Ok(());
+            }
+            (_, Arg::Undo, _) => {
+                return reminder_file.undo(false);
+            }
+            (_, Arg::UnFire, _) => {
+                return reminder_file.undo(true);
+            }
+            (_, Arg::Redo, _) => {
+                return reminder_file.redo();
+            }
+            (_, Arg::History, _) => {
+                reminder_file.history();
+                return Ok(());
+            }
+            (_, Arg::Missed, _) => {
+                reminder_file.missed();
+                return Ok(());
+            }
+            (_, Arg::Doctor(fix), _) => {
+                if fix {
+                    reminder_file.save_history(Op::Repair);
+                }
+                return reminder_file.doctor(fix);
+            }
+            (_, Arg::Clear, _) => {
+                reminder_file.save_history(Op::Clear);
+                return reminder_file.clear();
+            }
+            (_, Arg::Remove, Arg::Title(titl)) | (Arg::Title(titl), Arg::Remove, _) => {
+                reminder_file.save_history(Op::Remove);
+                return reminder_file.remove(&titl);
+            }
+            (_, Arg::Remove, _) => return reminder_file.remove_last(),
+            // skip 3 "reminder" | skip "reminder" 3 | "reminder" skip 3
+            // skip "reminder" | "reminder" skip | skip3 "reminder" | "reminder" skip3
+            (Arg::Skip(0), Arg::Number(skips), Arg::Title(title))
+            | (Arg::Skip(0), Arg::Title(title), Arg::Number(skips))
+            | (Arg::Title(title), Arg::Skip(0), Arg::Number(skips))
+            | (_, Arg::Skip(skips), Arg::Title(title))
+            | (_, Arg::Title(title), Arg::Skip(skips)) => {
+                reminder_file.save_history(Op::Skip);
+                return reminder_file.skip(&title, skips.max(1));
+            }
+            // skip | skip 3 | skip3
+            (_, Arg::Skip(0), Arg::Number(skips)) | (_, Arg::Skip(skips), _) => {
+                reminder_file.save_history(Op::Skip);
+                return reminder_file.skip_next(skips.max(1));
+            }
+            _ => {}
+        }
+    }
+
+    let mut reminder = parse_reminder(&tokens, &config)?.reminder;
+    reminder.update();
+    reminder_file.save_history(Op::Add);
+    reminder_file.append(reminder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDateTime;
+
+    #[test]
+    fn test_num_str_num() {
+        assert_eq!(num_str_num("32week4"), (32, "week".to_string(), 4));
+        assert_eq!(num_str_num("10days"), (10, "days".to_string(), 0));
+        assert_eq!(num_str_num("months5"), (0, "months".to_string(), 5));
+        assert_eq!(num_str_num("year"), (0, "year".to_string(), 0));
+    }
+
+    fn to_args(str: &[&str]) -> Vec<String> {
+        str.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn tokens(args: &[&str], config: &Config) -> Result<Vec<Arg>, Error> {
+        tokenize(&to_args(&[&["remind"], args].concat()), config)
+    }
+
+    fn title(str: &str) -> Arg {
+        Arg::Title(str.to_owned())
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let args = to_args(&["remind", "3w", "write homework"]);
+        let tokens = tokenize(&args, &Config::default()).unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Arg::TimeUnit(TimeUnit::Week(3)),
+                Arg::Title("write homework".to_string())
+            ]
+        );
+
+        let args = to_args(&["remind", "1m", "egg ready", "rep4", "skip", "3"]);
+        let tokens = tokenize(&args, &Config::default()).unwrap();
+        assert_eq!(
+            tokens,
+            [
+                Arg::TimeUnit(TimeUnit::Minute(1)),
+                Arg::Title("egg ready".to_string()),
+                Arg::Repeat(4),
+                Arg::Skip(0),
+                Arg::Number(3)
+            ]
+        );
+
+        let args = to_args(&["remind", "july", "4", "pay", "12:30"]);
+        let tokens = tokenize(&args, &Config::default()).unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Arg::Month(6),
+                Arg::Number(4),
+                Arg::Title("pay".to_string()),
+                // h:mm always gives 0 seconds, comparing against the current second only passed at :00
+                Arg::Time(12, 30, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fire_batch() {
+        let dir = std::env::temp_dir().join(format!("remind_test_batch_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("reminders.txt");
+        let mut reminder_file = ReminderFile::new(&file);
+        // late and dropped, so no notification is shown while testing
+        reminder_file.set_catch_up(CatchUp::Drop);
+        for title in ["tea", "laundry"] {
+            reminder_file
+                .append(Reminder {
+                    title: title.to_owned(),
+                    interval: Interval {
+                        hours: 1,
+                        ..Default::default()
+                    },
+                    end_time: Local::now() - Duration::minutes(150),
+                    repeats: 0,
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+        reminder_file.refresh().unwrap();
+
+        // every save journals what it changed, so one entry means one save
+        let entries = journal::Journal::new(file.with_extension("journal")).load();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].op, Op::Fire);
+        assert_eq!(entries[0].changes.len(), 2);
+        let contents = std::fs::read_to_string(&file).unwrap();
+        assert_eq!(contents.lines().count(), 3);
+        assert!(reminder_file.time_until_next().unwrap() > std::time::Duration::ZERO);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_late_wake() {
+        let dir = std::env::temp_dir().join(format!("remind_test_late_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("reminders.txt");
+        let mut reminder_file = ReminderFile::new(&file);
+        // daemon woke up 150 minutes after first of 3 hourly occurrences
+        let reminders = [
+            ("water", None, Duration::minutes(150)),
+            ("stretch", Some(CatchUp::Each), Duration::minutes(150)),
+            ("mail", Some(CatchUp::Drop), Duration::minutes(150)),
+            // due just now, not missed
+            ("tea", Some(CatchUp::Drop), Duration::seconds(10)),
+        ];
+        for (title, catch_up, ago) in reminders {
+            reminder_file
+                .append(Reminder {
+                    title: title.to_owned(),
+                    interval: Interval {
+                        hours: 1,
+                        ..Default::default()
+                    },
+                    end_time: Local::now() - ago,
+                    repeats: 0,
+                    catch_up,
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+        let (changed, notifications) = reminder_file.fire_due();
+        assert!(changed);
+        let shown = |title: &str, body: Option<&str>| (title.to_owned(), body.map(str::to_owned));
+        assert_eq!(
+            notifications,
+            [
+                shown("water", Some("missed 3 times")),
+                shown("stretch", None),
+                shown("stretch", None),
+                shown("stretch", None),
+                shown("tea", None),
+            ]
+        );
+        assert_eq!(reminder_file.fire_due(), (false, Vec::new()));
+
+        let missed_file = file.with_extension("missed");
+        let missed = std::fs::read_to_string(&missed_file).unwrap();
+        let missed: Vec<&str> = missed.lines().collect();
+        assert_eq!(missed.len(), 3);
+        assert!(missed[0].starts_with("\"water\" missed 3 times since "));
+        assert!(missed[1].starts_with("\"stretch\" missed 3 times since "));
+        assert!(missed[2].starts_with("\"mail\" missed 3 times since "));
+        assert!(missed.iter().all(|line| line.contains(", caught up at ")));
+        reminder_file.missed();
+        assert!(!missed_file.exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_doctor() {
+        let dir = std::env::temp_dir().join(format!("remind_test_doctor_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("reminders.txt");
+        let rejected_file = file.with_extension("rejected");
+        let tea = Reminder {
+            id: "k2".to_owned(),
+            title: "tea".to_owned(),
+            ..Default::default()
+        };
+        let nap = Reminder {
+            id: "q7".to_owned(),
+            title: "nap".to_owned(),
+            repeats: 0,
+            ..Default::default()
+        };
+        let lines = [
+            "# remind reminders v2\n",
+            &tea.serialize(),
+            "title=laundry\n",
+            &nap.serialize(),
+            "garbage\n",
+        ];
+        std::fs::write(&file, lines.concat()).unwrap();
+        let mut reminder_file = ReminderFile::new(&file);
+        reminder_file.load().unwrap();
+
+        // unreadable lines leave reminders file with reason on the line before them
+        let contents = std::fs::read_to_string(&file).unwrap();
+        assert!(contents.contains(&tea.serialize()) && contents.contains(&nap.serialize()));
+        assert!(!contents.contains("laundry") && !contents.contains("garbage"));
+        let rejected = std::fs::read_to_string(&rejected_file).unwrap();
+        let rejected: Vec<&str> = rejected.lines().collect();
+        assert_eq!(rejected.len(), 4);
+        assert!(
+            rejected[0].starts_with("# ") && rejected[0].ends_with(" line 3: missing end time")
+        );
+        assert_eq!(rejected[1], "title=laundry");
+        assert!(rejected[2].ends_with(" line 5: field \"garbage\" is missing '='"));
+        assert_eq!(rejected[3], "garbage");
+
+        let diagnosis = reminder_file.diagnose();
+        assert_eq!(
+            diagnosis
+                .rejected
+                .iter()
+                .map(|(_, line)| line.as_str())
+                .collect::<Vec<_>>(),
+            ["title=laundry", "garbage"]
+        );
+        assert_eq!(diagnosis.problems.len(), 1);
+        assert_eq!(diagnosis.problems[0].0, 1);
+        assert!(diagnosis.problems[0]
+            .1
+            .ends_with("repeats forever without interval"));
+
+        // only reports
+        reminder_file.doctor(false).unwrap();
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), contents);
+        assert_eq!(reminder_file.diagnose().rejected.len(), 2);
+
+        reminder_file.doctor(true).unwrap();
+        assert_eq!(reminder_file.get("nap").unwrap().repeats, 1);
+        assert!(std::fs::read_to_string(&file)
+            .unwrap()
+            .contains("title=laundry\t"));
+        // what could not be repaired stays in sidecar
+        assert_eq!(
+            std::fs::read_to_string(&rejected_file)
+                .unwrap()
+                .lines()
+                .last(),
+            Some("garbage")
+        );
+        let diagnosis = reminder_file.diagnose();
+        assert_eq!(diagnosis.rejected.len(), 1);
+        assert!(diagnosis.problems.is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_lock() {
+        let dir = std::env::temp_dir().join(format!("remind_test_lock_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("reminders.txt");
+        let mut reminder_file = ReminderFile::new(&file);
+        let lock = reminder_file.lock().unwrap();
+        let lock_file = std::fs::File::open(file.with_extension("lock")).unwrap();
+        assert!(matches!(
+            lock_file.try_lock(),
+            Err(std::fs::TryLockError::WouldBlock)
+        ));
+
+        // another process waits until lock is released
+        let (locked, waiting) = std::sync::mpsc::channel();
+        let other = ReminderFile::new(&file);
+        let thread = std::thread::spawn(move || {
+            let _lock = other.lock().unwrap();
+            locked.send(()).unwrap();
+        });
+        let wait = std::time::Duration::from_millis(200);
+        assert!(waiting.recv_timeout(wait).is_err());
+        drop(lock);
+        assert!(waiting.recv_timeout(wait * 10).is_ok());
+        thread.join().unwrap();
+        assert!(lock_file.try_lock().is_ok());
+
+        // saves replace reminders file instead of writing into it
+        reminder_file
+            .append(Reminder {
+                title: "tea".to_owned(),
+                ..Default::default()
+            })
+            .unwrap();
+        let before = std::fs::File::open(&file).unwrap();
+        reminder_file
+            .append(Reminder {
+                title: "laundry".to_owned(),
+                ..Default::default()
+            })
+            .unwrap();
+        let old = std::io::read_to_string(before).unwrap();
+        assert!(old.contains("tea") && !old.contains("laundry"));
+        assert!(std::fs::read_to_string(&file).unwrap().contains("laundry"));
+        assert!(!file.with_extension("tmp").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_catch_up() {
+        let args = to_args(&["remind", "hourly", "stretch", "catch-up", "each"]);
+        assert_eq!(
+            tokenize(&args, &Config::default()).unwrap(),
+            vec![
+                Arg::Repeat(0),
+                Arg::TimeUnit(TimeUnit::Hour(1)),
+                Arg::Title("stretch".to_string()),
+                Arg::CatchUp(CatchUp::Each),
+            ]
+        );
+        let args = to_args(&["remind", "catch-up", "train"]);
+        assert_eq!(
+            tokenize(&args, &Config::default()).unwrap(),
+            vec![Arg::Title("catch-up train".to_string())]
+        );
+
+        // daemon was away for 3 occurrences
+        let mut reminder = Reminder {
+            id: String::new(),
+            title: "water".to_string(),
+            interval: Interval {
+                days: 1,
+                ..Default::default()
+            },
+            end_time: Local::now() - Duration::hours(60),
             repeats: 0,
             skips: 0,
             weekdays: 0,
-        });
+            catch_up: None,
+            rrule: None,
+            cron: None,
+            window: None,
+        };
+        assert_eq!(reminder.update(), (3, false));
+        assert!(reminder.end_time > Local::now());
+    }
+
+    #[test]
+    fn test_rrule() {
+        let at = |str: &str| NaiveDateTime::parse_from_str(str, "%Y-%m-%d %H:%M").unwrap();
+        let take = |rule: &str, start: &str, n: usize| {
+            let rule: RRule = rule.parse().unwrap();
+            rule.iter(at(start)).take(n).collect::<Vec<_>>()
+        };
+
+        // 2nd tuesday of each month
+        assert_eq!(
+            take("RRULE:FREQ=MONTHLY;BYDAY=2TU", "2024-10-01 09:00", 3),
+            [
+                at("2024-10-08 09:00"),
+                at("2024-11-12 09:00"),
+                at("2024-12-10 09:00")
+            ]
+        );
+        // last weekday of the month
+        assert_eq!(
+            take(
+                "FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1",
+                "2024-08-01 17:00",
+                3
+            ),
+            [
+                at("2024-08-30 17:00"),
+                at("2024-09-30 17:00"),
+                at("2024-10-31 17:00")
+            ]
+        );
+        // every 3 weeks on monday and thursday
+        assert_eq!(
+            take("FREQ=WEEKLY;INTERVAL=3;BYDAY=MO,TH", "2024-10-07 08:00", 4),
+            [
+                at("2024-10-07 08:00"),
+                at("2024-10-10 08:00"),
+                at("2024-10-28 08:00"),
+                at("2024-10-31 08:00")
+            ]
+        );
+        // second to last day of february, only 2 times
+        assert_eq!(
+            take(
+                "FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=-2;COUNT=2",
+                "2024-01-01 12:00",
+                5
+            ),
+            [at("2024-02-28 12:00"), at("2025-02-27 12:00")]
+        );
+        // last friday of the year
+        assert_eq!(
+            take("FREQ=YEARLY;BYDAY=-1FR", "2024-01-01 12:00", 1),
+            [at("2024-12-27 12:00")]
+        );
+        assert_eq!(
+            take("FREQ=DAILY;UNTIL=20241003", "2024-10-01 12:00", 5).len(),
+            3
+        );
+
+        let rule: RRule = "FREQ=WEEKLY;INTERVAL=2".parse().unwrap();
+        assert_eq!(
+            rule.next_after(at("2024-10-07 08:00")),
+            Some(at("2024-10-21 08:00"))
+        );
+        assert_eq!(rule.to_string(), "FREQ=WEEKLY;INTERVAL=2");
+        assert!("FREQ=MONTHLY;BYDAY=9XX".parse::<RRule>().is_err());
+        assert!("BYDAY=MO".parse::<RRule>().is_err());
+        for freq in ["YEARLY", "MONTHLY", "WEEKLY", "DAILY", "HOURLY"] {
+            let rule = format!("FREQ={freq};INTERVAL=4000000000");
+            assert!(rule.parse::<RRule>().is_err(), "{rule}");
+        }
+        // no period past the last representable date, instead of overflowing
+        let rule: RRule = "FREQ=MINUTELY;INTERVAL=4000000000".parse().unwrap();
+        assert_eq!(rule.iter(at("2024-10-07 08:00")).nth(5), None);
+        assert!("FREQ=DAILY;COUNT=2;UNTIL=20240101"
+            .parse::<RRule>()
+            .is_err());
+    }
+
+    #[test]
+    fn test_cron() {
+        let at = |str: &str| NaiveDateTime::parse_from_str(str, "%Y-%m-%d %H:%M:%S").unwrap();
+        let next = |expr: &str, after: &str| {
+            let cron: Cron = expr.parse().unwrap();
+            cron.next_after(at(after))
+        };
+
+        // friday evening -> monday morning
+        assert_eq!(
+            next("0 9 * * 1-5", "2024-10-18 17:00:00"),
+            Some(at("2024-10-21 09:00:00"))
+        );
+        assert_eq!(
+            next("*/15 9-17 * * mon-fri", "2024-10-21 09:07:00"),
+            Some(at("2024-10-21 09:15:00"))
+        );
+        // day and weekday both restricted, either matches
+        assert_eq!(
+            next("0 0 13 * fri", "2024-10-01 00:00:00"),
+            Some(at("2024-10-04 00:00:00"))
+        );
+        assert_eq!(
+            next("30 0 12 1 jan,jul *", "2024-02-01 00:00:00"),
+            Some(at("2024-07-01 12:00:30"))
+        );
+        assert_eq!(
+            next("@monthly", "2024-10-18 00:00:00"),
+            Some(at("2024-11-01 00:00:00"))
+        );
+        assert_eq!(next("0 0 30 feb *", "2024-01-01 00:00:00"), None);
+        assert!("0 9 * *".parse::<Cron>().is_err());
+        assert!("0 25 * * *".parse::<Cron>().is_err());
+        assert!("0 9 * * 5-1".parse::<Cron>().is_err());
+
+        let args = to_args(&["remind", "cron", "0 9 * * 1-5", "standup"]);
+        let tokens = tokenize(&args, &Config::default()).unwrap();
+        assert!(matches!(tokens[0], Arg::Cron(_)));
+        assert_eq!(tokens[1], Arg::Title("standup".to_string()));
+    }
+
+    #[test]
+    fn test_serialize() {
+        let reminder = Reminder {
+            id: String::new(),
+            title: "odd\ttitle⌠with\nnewline \\n".to_string(),
+            interval: Interval {
+                days: 1,
+                hours: 2,
+                ..Default::default()
+            },
+            end_time: local_time(
+                NaiveDateTime::parse_from_str("2029-02-28 12:30:15", "%Y-%m-%d %H:%M:%S").unwrap(),
+            ),
+            repeats: 3,
+            skips: 1,
+            weekdays: MONDAY | FRIDAY,
+            catch_up: Some(CatchUp::Drop),
+            rrule: None,
+            cron: Some("0 9 * * 1-5".parse().unwrap()),
+            window: NaiveTime::from_hms_opt(22, 0, 0).zip(NaiveTime::from_hms_opt(6, 0, 0)),
+        };
+        let line = reminder.serialize();
+        assert_eq!(line.lines().count(), 1);
+        let parsed = Reminder::deserialize(line.trim_end()).unwrap();
+        assert_eq!(parsed.serialize(), line);
+        assert_eq!(parsed.title, reminder.title);
+        assert_eq!(parsed.end_time, reminder.end_time);
+
+        let v1 = Reminder::deserialize_v1(
+            "pay⌠00-01-00 00:00:00⌠29-02-28 12:30:00⌠0⌠0⌠sun mon tue wed thu fri sat",
+        )
+        .unwrap();
+        assert_eq!(v1.title, "pay");
+        assert_eq!(v1.interval.months, 1);
+        assert!(Reminder::deserialize_v1("pay⌠00-01-00").is_err());
+        assert!(Reminder::deserialize_v1("pay⌠01-00⌠29-02-28 12:30:00⌠0⌠0⌠").is_err());
+        assert!(Reminder::deserialize("title=x\tend=never").is_err());
+        assert!(Reminder::deserialize("title=x\\").is_err());
+
+        let repaired = Reminder::repair("title=x\tinterval=bad\trepeats=3").unwrap();
+        assert_eq!((repaired.title.as_str(), repaired.repeats), ("x", 3));
+        let repaired = Reminder::repair("water⌠00-01⌠29-02-28 12:30:00⌠2").unwrap();
+        assert_eq!((repaired.title.as_str(), repaired.repeats), ("water", 2));
+        assert!(Reminder::repair("junk").is_none());
+    }
+
+    #[test]
+    fn test_generate_id() {
+        let mut ids: Vec<String> = Vec::new();
+        for _ in 0..200 {
+            let id = generate_id(|id| ids.iter().any(|taken| taken == id));
+            // ids must never be mistaken for units, weekdays or commands
+            let args = to_args(&["remind", &id]);
+            assert_eq!(
+                tokenize(&args, &Config::default()).unwrap(),
+                vec![Arg::Title(id.clone())]
+            );
+            ids.push(id);
+        }
+    }
+
+    #[test]
+    fn test_print_help() {
+        print_help(&Vocab::default());
+    }
+
+    #[test]
+    fn test_reminder_file() {
+        let mut reminder_file = ReminderFile::new("test_reminders.txt");
+        reminder_file
+            .append(Reminder {
+                id: String::new(),
+                title: "Test Reminder".to_string(),
+                interval: Interval::default(),
+                end_time: Local::now(),
+                repeats: 0,
+                skips: 0,
+                weekdays: 0,
+                catch_up: None,
+                rrule: None,
+                cron: None,
+                window: None,
+            })
+            .unwrap();
         reminder_file.list();
-        reminder_file.remove("test rem");
+        reminder_file.remove("test rem").unwrap();
         std::fs::remove_file("test_reminders.txt").unwrap();
     }
+
+    #[test]
+    fn test_edit() {
+        let tokens = tokenize(
+            &to_args(&["remind", "edit", "kq27", "3pm", "rep", "4"]),
+            &Config::default(),
+        )
+        .unwrap();
+        assert_eq!(tokens[0], Arg::Edit("kq27".to_owned()));
+        let parsed = parse_reminder(&tokens[1..], &Config::default()).unwrap();
+        assert!(!parsed.title && !parsed.schedule && parsed.repeats);
+        assert_eq!(parsed.time, NaiveTime::from_hms_opt(15, 0, 0));
+        // edit is only a command as first argument
+        assert!(!tokenize(
+            &to_args(&["remind", "5m", "edit", "video"]),
+            &Config::default()
+        )
+        .unwrap()
+        .iter()
+        .any(|tok| matches!(tok, Arg::Edit(_))));
+
+        let mut reminder = Reminder {
+            title: "stretch".to_owned(),
+            interval: Interval {
+                days: 7,
+                ..Default::default()
+            },
+            end_time: Local::now() + Duration::days(3),
+            repeats: 0,
+            ..Default::default()
+        };
+        let date = reminder.end_time.date_naive();
+        parsed.apply(&mut reminder).unwrap();
+        assert_eq!(reminder.title, "stretch");
+        assert_eq!(reminder.interval.days, 7);
+        assert_eq!(reminder.repeats, 4);
+        assert_eq!(reminder.end_time.date_naive(), date);
+        assert_eq!(reminder.end_time.hour(), 15);
+
+        let tokens = tokenize(
+            &to_args(&["remind", "edit", "stretch", "2h", "walk"]),
+            &Config::default(),
+        )
+        .unwrap();
+        parse_reminder(&tokens[1..], &Config::default())
+            .unwrap()
+            .apply(&mut reminder)
+            .unwrap();
+        assert_eq!(reminder.title, "walk");
+        assert_eq!(reminder.interval.hours, 2);
+        assert_eq!(reminder.interval.days, 0);
+        assert_eq!(reminder.repeats, 4);
+    }
+
+    #[test]
+    fn test_commands() {
+        // commands that are common words only count as first argument
+        let config = Config::default();
+        for (args, reminder_title) in [
+            (&["5m", "check", "email"][..], "check email"),
+            (&["5m", "check", "fix"], "check fix"),
+            (&["1h", "audit", "books"], "audit books"),
+            (&["30m", "missed", "call", "back"], "missed call back"),
+            (&["5m", "fsck", "disk"], "fsck disk"),
+        ] {
+            assert_eq!(tokens(args, &config).unwrap()[1..], [title(reminder_title)]);
+        }
+        assert_eq!(tokens(&["check"], &config).unwrap(), [Arg::Doctor(false)]);
+        assert_eq!(tokens(&["log"], &config).unwrap(), [Arg::History]);
+        assert_eq!(
+            tokens(&["--dry-run", "missed"], &config).unwrap(),
+            [Arg::Explain, Arg::Missed]
+        );
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let dir = std::env::temp_dir().join(format!("remind_test_undo_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("reminders.txt");
+        let mut reminder_file = ReminderFile::new(&file);
+        let contents = || std::fs::read_to_string(&file).unwrap();
+        for title in ["tea", "laundry"] {
+            reminder_file.save_history(Op::Add);
+            reminder_file
+                .append(Reminder {
+                    title: title.to_owned(),
+                    ..Default::default()
+                })
+                .unwrap();
+        }
+        // nothing changed, so nothing to record
+        reminder_file.save_history(Op::Remove);
+        reminder_file.remove("zzzzzz").unwrap();
+        let both = contents();
+        assert!(both.contains("tea") && both.contains("laundry"));
+
+        reminder_file.undo(false).unwrap();
+        assert!(contents().contains("tea") && !contents().contains("laundry"));
+        reminder_file.undo(false).unwrap();
+        assert!(!contents().contains("tea"));
+        reminder_file.redo().unwrap();
+        reminder_file.redo().unwrap();
+        assert_eq!(contents(), both);
+        reminder_file.history();
+
+        // new change after undo drops what could be redone
+        reminder_file.undo(false).unwrap();
+        reminder_file.save_history(Op::Clear);
+        reminder_file.clear().unwrap();
+        reminder_file.redo().unwrap();
+        assert!(!contents().contains("tea"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_journal() {
+        let file = std::env::temp_dir().join(format!("remind_test_{}.journal", std::process::id()));
+        let mut journal = journal::Journal::new(&file);
+        let tea = Reminder {
+            id: "kq27".to_owned(),
+            title: "tea\twith\\milk".to_owned(),
+            ..Default::default()
+        };
+        let mut green_tea = tea.clone();
+        green_tea.title = "green tea".to_owned();
+        let changes = journal::diff(&[], std::slice::from_ref(&tea));
+        journal.append(journal::Actor::Cli, Op::Add, None, changes);
+        let changes = journal::diff(std::slice::from_ref(&tea), &[green_tea]);
+        journal.append(journal::Actor::Daemon, Op::Edit, None, changes);
+        journal.append(journal::Actor::Cli, Op::Undo, Some(2), Vec::new());
+
+        let entries = journal.load();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[1].actor, journal::Actor::Daemon);
+        let change = &entries[1].changes[0];
+        assert_eq!(change.before.as_ref().unwrap().title, tea.title);
+        assert_eq!(change.after.as_ref().unwrap().title, "green tea");
+        assert_eq!(journal::undo_redo_stacks(&entries), (vec![1], vec![2]));
+
+        journal.set_retention("2".parse().unwrap());
+        journal.append(journal::Actor::Cli, Op::Redo, Some(2), Vec::new());
+        let entries = journal.load();
+        assert_eq!(entries.iter().map(|e| e.seq).collect::<Vec<_>>(), [3, 4]);
+        assert_eq!("30d".parse(), Ok(Retention::Days(30)));
+        assert!("soon".parse::<Retention>().is_err());
+        std::fs::remove_file(&file).unwrap();
+    }
+
+    #[test]
+    fn test_undo_keeps_fires() {
+        let dir = std::env::temp_dir().join(format!("remind_test_fire_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("reminders.txt");
+        let mut reminder_file = ReminderFile::new(&file);
+        let contents = || std::fs::read_to_string(&file).unwrap();
+        reminder_file.save_history(Op::Add);
+        reminder_file
+            .append(Reminder {
+                title: "tea".to_owned(),
+                ..Default::default()
+            })
+            .unwrap();
+        reminder_file.save_history(Op::Edit);
+        reminder_file
+            .edit("tea", |reminder| {
+                reminder.title = "green tea".to_owned();
+                Ok(())
+            })
+            .unwrap();
+        // stands in for daemon consuming occurrences
+        reminder_file.save_history(Op::Fire);
+        reminder_file.skip("green tea", 2).unwrap();
+
+        reminder_file.undo(false).unwrap();
+        assert!(contents().contains("title=tea\t") && contents().contains("skips=2"));
+        reminder_file.undo(true).unwrap();
+        assert!(contents().contains("title=tea\t") && contents().contains("skips=0"));
+        reminder_file.undo(true).unwrap();
+        reminder_file.redo().unwrap();
+        assert!(contents().contains("skips=2"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_location_args() {
+        let mut args = to_args(&["remind", "list", "-l", "Work"]);
+        assert_eq!(
+            take_location_args(&mut args),
+            Ok((None, Some("work".to_owned())))
+        );
+        assert_eq!(args, to_args(&["remind", "list"]));
+
+        let mut args = to_args(&["remind", "--file=/tmp/r.txt", "9am", "standup"]);
+        let (file, list) = take_location_args(&mut args).unwrap();
+        assert_eq!(file, Some(PathBuf::from("/tmp/r.txt")));
+        assert_eq!(list, None);
+        assert_eq!(args, to_args(&["remind", "9am", "standup"]));
+
+        assert!(take_location_args(&mut to_args(&["remind", "-l"])).is_err());
+        assert!(take_location_args(&mut to_args(&["remind", "-l", "../x"])).is_err());
+    }
+
+    #[test]
+    fn test_config() {
+        let config: Config = r#"
+            [defaults]
+            time = "9am"
+            notification-timeout = 10
+            catch-up = "drop"
+            history-retention = "30d"
+
+            [aliases]
+            lunch = "12:30"
+            standup = 'work 9:15 repeat "daily standup"'
+        "#
+        .parse()
+        .unwrap();
+        assert_eq!(config.default_time, NaiveTime::from_hms_opt(9, 0, 0));
+        assert_eq!(config.notification_timeout, Some(10));
+        assert_eq!(config.catch_up, Some(CatchUp::Drop));
+        assert_eq!(config.history_retention, Some(Retention::Days(30)));
+
+        let tokens = tokenize(&to_args(&["remind", "Lunch", "eat"]), &config).unwrap();
+        assert_eq!(
+            tokens,
+            vec![Arg::Time(12, 30, 0), Arg::Title("eat".to_owned())]
+        );
+        let tokens = tokenize(&to_args(&["remind", "standup"]), &config).unwrap();
+        assert_eq!(
+            tokens[0],
+            Arg::WeekDay(MONDAY | TUESDAY | WEDNESDAY | THURSDAY | FRIDAY)
+        );
+        assert!(tokens.contains(&Arg::Title("daily standup".to_owned())));
+
+        // date without time of day gets default time
+        let tokens = tokenize(&to_args(&["remind", "dec", "24", "gifts"]), &config).unwrap();
+        let parsed = parse_reminder(&tokens, &config).unwrap();
+        assert_eq!(
+            parsed.reminder.end_time.time(),
+            config.default_time.unwrap()
+        );
+
+        assert!("[defaults]\ntime = \"soon\"".parse::<Config>().is_err());
+        assert!("colour = 1".parse::<Config>().is_err());
+        assert!("[aliases]\n\"two words\" = \"9am\""
+            .parse::<Config>()
+            .is_err());
+        assert!("[words]\nsoon = \"later\"".parse::<Config>().is_err());
+    }
+
+    #[test]
+    fn test_vocab() {
+        let vocab = Vocab::default();
+        // exact, prefix and typo
+        assert_eq!(vocab.lookup("Wed"), Some(Word::WeekDay(WEDNESDAY)));
+        assert_eq!(vocab.lookup("wedn"), Some(Word::WeekDay(WEDNESDAY)));
+        assert_eq!(
+            vocab.lookup("mintue"),
+            Some(Word::Unit(TimeUnit::Minute(0)))
+        );
+        assert_eq!(vocab.lookup("tomorow"), Some(Word::Tomorrow));
+        assert_eq!(vocab.lookup("tommorrow"), Some(Word::Tomorrow));
+        assert_eq!(vocab.lookup("septembre"), Some(Word::Month(8)));
+        // misspelling kept from before the registry, too short to be caught as a typo
+        let yearly = Some(Word::Every(TimeUnit::Year(0)));
+        assert_eq!(vocab.lookup("anual"), yearly);
+        assert_eq!(vocab.lookup("anually"), yearly);
+        // shortest completion wins
+        assert_eq!(vocab.lookup("week"), Some(Word::Unit(TimeUnit::Week(0))));
+        assert_eq!(
+            vocab.lookup("weeke"),
+            Some(Word::WeekDay(SUNDAY | SATURDAY))
+        );
+        // too short or too far off to guess
+        assert_eq!(vocab.lookup("frm"), None);
+        assert_eq!(vocab.lookup("house"), None);
+        assert_eq!(vocab.lookup("egg ready"), None);
+        // commands only match exactly
+        assert_eq!(vocab.lookup("rm"), Some(Word::Remove));
+        assert_eq!(vocab.lookup("remmove"), None);
+        assert_eq!(vocab.lookup("histry"), None);
+
+        assert_eq!(vocab.weekdays("mo-th"), Some(MONDAY | THURSDAY));
+        assert_eq!(vocab.weekdays("sat+sun"), Some(SUNDAY | SATURDAY));
+        assert_eq!(vocab.weekdays("mo,xx"), None);
+        let tokens = tokenize(&to_args(&["remind", "mo|th", "gym"]), &Config::default()).unwrap();
+        assert_eq!(tokens[0], Arg::WeekDay(MONDAY | THURSDAY));
+        let tokens = tokenize(&to_args(&["remind", "h"]), &Config::default()).unwrap();
+        assert_eq!(tokens, vec![Arg::Help]);
+        let tokens = tokenize(&to_args(&["remind", "2h", "dailly"]), &Config::default()).unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Arg::TimeUnit(TimeUnit::Hour(2)),
+                Arg::Repeat(0),
+                Arg::TimeUnit(TimeUnit::Day(1))
+            ]
+        );
+
+        // config words
+        let config: Config = "[words]\nwoche = \"week\"\nm = \"month\"".parse().unwrap();
+        let tokens = tokenize(&to_args(&["remind", "2woche", "3m"]), &config).unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Arg::TimeUnit(TimeUnit::Week(2)),
+                Arg::TimeUnit(TimeUnit::Month(3))
+            ]
+        );
+        assert!(config.vocab.help().contains("woche"));
+    }
+
+    #[test]
+    fn test_error() {
+        let parse = |args: &[&str]| {
+            let config = Config::default();
+            tokenize(&to_args(args), &config).and_then(|tokens| parse_reminder(&tokens, &config))
+        };
+        let error = parse(&["remind", "feb", "30", "x"]).err().unwrap();
+        assert_eq!(error.exit_code(), 3);
+        assert!(error.to_string().ends_with("did you mean feb 28?"));
+        let error = parse(&["remind", "apr", "31", "2030", "x"]).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "apr 31 2030 does not exist\n  did you mean apr 30 2030?"
+        );
+        assert!(parse(&["remind", "feb", "29", "2028", "x"]).is_ok());
+        // earlier today
+        let now = Local::now();
+        let (month, day, year) = (month_name(now.month0()), now.day(), now.year());
+        let (day, year) = (day.to_string(), year.to_string());
+        assert!(matches!(
+            parse(&["remind", &month, &day, &year, "0:00", "x"]),
+            Err(Error::Schedule { .. })
+        ));
+
+        let error = parse(&["remind", "25:00", "x"]).err().unwrap();
+        assert_eq!(error.exit_code(), 2);
+        assert_eq!(
+            error.to_string(),
+            "invalid \"25:00\": not a time of day\n  did you mean 23:00?"
+        );
+        assert!(parse(&["remind", "13pm", "x"]).is_err());
+        assert!(parse(&["remind", "0am", "x"]).is_err());
+        let error = parse(&["remind", "4000000000", "days", "x"]).err().unwrap();
+        assert_eq!(error.exit_code(), 3);
+        let error = parse(&["remind", "every", "4000000000", "weeks", "x"])
+            .err()
+            .unwrap();
+        assert_eq!(error.exit_code(), 3);
+        assert!(parse(&["remind", "12:75", "x"]).is_err());
+        assert!(parse(&["remind", "FREQ=SOMETIMES", "x"]).is_err());
+        assert!(parse(&["remind", "cron", "0 9 * *", "x"]).is_err());
+        assert!(matches!(
+            parse(&["remind", "july", "x"]),
+            Err(Error::Parse { .. })
+        ));
+
+        let mut args = to_args(&["remind", "-l", "a/b", "list"]);
+        assert_eq!(take_location_args(&mut args).err().unwrap().exit_code(), 2);
+        assert_eq!(Error::storage("x", "denied").exit_code(), 4);
+    }
+
+    #[test]
+    fn test_explain() {
+        let tokens = tokenize(
+            &to_args(&["remind", "explain", "edit", "kq27", "3pm"]),
+            &Config::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Arg::Explain,
+                Arg::Edit("kq27".to_owned()),
+                Arg::Time(15, 0, 0)
+            ]
+        );
+        let tokens = tokenize(
+            &to_args(&["remind", "monday", "3pm", "rep", "3", "x", "--dry-run"]),
+            &Config::default(),
+        )
+        .unwrap();
+        assert_eq!(tokens.last(), Some(&Arg::Explain));
+
+        // commands are refused under explain instead of run, reminders stay as they were
+        let file = std::env::temp_dir().join(format!("remind_explain_{}.txt", std::process::id()));
+        let mut reminder_file = ReminderFile::new(&file);
+        reminder_file
+            .append(Reminder {
+                title: "tea".to_owned(),
+                ..Default::default()
+            })
+            .unwrap();
+        let before = std::fs::read_to_string(&file).unwrap();
+        for args in [
+            &["remind", "explain", "clear"][..],
+            &["remind", "clear", "--dry-run"],
+            &["remind", "--dry-run", "doctor", "fix"],
+            &["remind", "--dry-run", "undo"],
+            &["remind", "explain", "remove", "tea"],
+            &["remind", "--dry-run", "skip"],
+        ] {
+            let tokens = tokenize(&to_args(args), &Config::default()).unwrap();
+            assert!(tokens.contains(&Arg::Explain));
+            let tokens = tokens
+                .into_iter()
+                .filter(|tok| *tok != Arg::Explain)
+                .collect::<Vec<_>>();
+            let error = explain(&tokens, &Config::default(), &reminder_file).err();
+            assert_eq!(error.map(|e| e.exit_code()), Some(2));
+        }
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), before);
+        std::fs::remove_file(&file).unwrap();
+
+        // explain on an old file reads it as is, no upgrade, backup or ids saved
+        let file =
+            std::env::temp_dir().join(format!("remind_explain_v1_{}.txt", std::process::id()));
+        let v1 = "pay⌠00-01-00 00:00:00⌠29-02-28 12:30:00⌠0⌠0⌠sun mon tue wed thu fri sat\n";
+        std::fs::write(&file, v1).unwrap();
+        let mut reminder_file = ReminderFile::new(&file);
+        reminder_file.load_read_only();
+        let tokens = tokenize(
+            &to_args(&["remind", "edit", "pay", "3pm"]),
+            &Config::default(),
+        );
+        explain(&tokens.unwrap(), &Config::default(), &reminder_file).unwrap();
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), v1);
+        assert!(!file.with_extension("v1").exists());
+        std::fs::remove_file(&file).unwrap();
+
+        let tokens = tokenize(
+            &to_args(&["remind", "monday", "3pm", "rep", "3", "x"]),
+            &Config::default(),
+        )
+        .unwrap();
+        let mut reminder = parse_reminder(&tokens, &Config::default())
+            .unwrap()
+            .reminder;
+        reminder.update();
+        reminder.skips = 1;
+        let occurrences = reminder.occurrences().collect::<Vec<_>>();
+        assert_eq!(occurrences.len(), 3);
+        assert_eq!(
+            occurrences
+                .iter()
+                .map(|(_, skipped)| *skipped)
+                .collect::<Vec<_>>(),
+            [true, false, false]
+        );
+        for (time, _) in &occurrences {
+            assert_eq!(time.weekday(), chrono::Weekday::Mon);
+            assert_eq!(time.hour(), 15);
+        }
+        let days = occurrences[1].0.date_naive() - occurrences[0].0.date_naive();
+        assert_eq!(days.num_days(), 7);
+    }
+
+    #[test]
+    fn test_next() {
+        let tokens = tokenize(
+            &to_args(&["remind", "next", "kq27", "10"]),
+            &Config::default(),
+        );
+        assert_eq!(
+            tokens.unwrap(),
+            vec![Arg::Next(0), Arg::Title("kq27".to_owned()), Arg::Number(10)]
+        );
+
+        let mut args = to_args(&["remind", "list", "--expand", "3"]);
+        assert_eq!(take_expand_arg(&mut args), Ok(Some(3)));
+        assert_eq!(args, to_args(&["remind", "list"]));
+        let mut args = to_args(&["remind", "--expand", "list"]);
+        assert_eq!(take_expand_arg(&mut args), Ok(Some(UPCOMING)));
+        assert_eq!(args, to_args(&["remind", "list"]));
+        assert!(take_expand_arg(&mut to_args(&["remind", "--expand=many"])).is_err());
+
+        // skipped occurrences still use up repeats
+        let reminder = Reminder {
+            interval: Interval {
+                hours: 2,
+                ..Default::default()
+            },
+            repeats: 4,
+            skips: 2,
+            ..Default::default()
+        };
+        let occurrences = reminder.occurrences().take(10).collect::<Vec<_>>();
+        assert_eq!(
+            occurrences
+                .iter()
+                .map(|(_, skipped)| *skipped)
+                .collect::<Vec<_>>(),
+            [true, true, false, false]
+        );
+        assert_eq!(occurrences[3].0 - occurrences[0].0, Duration::hours(6));
+        // repeating forever without interval fires once
+        let reminder = Reminder {
+            repeats: 0,
+            ..Default::default()
+        };
+        assert_eq!(reminder.occurrences().take(10).count(), 1);
+        // interval past the last representable time ends the reminder
+        let reminder = Reminder {
+            interval: Interval {
+                days: 50_000_000,
+                ..Default::default()
+            },
+            repeats: 0,
+            ..Default::default()
+        };
+        assert_eq!(reminder.occurrences().take(10).count(), 2);
+    }
+
+    #[test]
+    fn test_agenda() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        let day = |day| NaiveDate::from_ymd_opt(2026, 10, day).unwrap();
+        let span = |str: &str| str.parse::<Span>().unwrap().dates(today);
+        assert_eq!(span("today"), (today, today));
+        assert_eq!(span("tomorrow"), (day(19), day(19)));
+        assert_eq!(span("week"), (today, day(24)));
+        assert_eq!(
+            span("month"),
+            (today, NaiveDate::from_ymd_opt(2026, 11, 17).unwrap())
+        );
+        assert_eq!(span("10d"), (today, day(27)));
+        assert_eq!(span("2w"), (today, day(31)));
+        assert_eq!(span("2026-10-25..2026-10-20"), (day(20), day(25)));
+        assert!("soon".parse::<Span>().is_err());
+        assert!("0d".parse::<Span>().is_err());
+        // a day per line, so spans stop at a year
+        assert_eq!(
+            span("366d").1,
+            NaiveDate::from_ymd_opt(2027, 10, 18).unwrap()
+        );
+        assert!("367d".parse::<Span>().is_err());
+        assert!("400000000d".parse::<Span>().is_err());
+        assert!("4000000000w".parse::<Span>().is_err());
+        assert!("2026-01-01..9999-12-31".parse::<Span>().is_err());
+        assert_eq!(
+            Span::Days(2).dates(NaiveDate::MAX),
+            (NaiveDate::MAX, NaiveDate::MAX)
+        );
+
+        let tokens = tokenize(
+            &to_args(&["remind", "agenda", "tomorrow"]),
+            &Config::default(),
+        );
+        assert_eq!(tokens.unwrap(), vec![Arg::Agenda(Span::Tomorrow)]);
+        let tokens = tokenize(
+            &to_args(&["remind", "3pm", "agenda", "review"]),
+            &Config::default(),
+        );
+        assert_eq!(tokens.unwrap()[1], Arg::Title("agenda review".to_owned()));
+
+        let now = Local::now();
+        let tomorrow =
+            local_time(now.date_naive().and_hms_opt(0, 0, 0).unwrap()) + Duration::days(1);
+        let daily = Reminder {
+            title: "daily".to_owned(),
+            interval: Interval {
+                days: 1,
+                ..Default::default()
+            },
+            end_time: tomorrow + Duration::hours(9),
+            repeats: 0,
+            skips: 1,
+            ..Default::default()
+        };
+        let once = Reminder {
+            title: "once".to_owned(),
+            end_time: tomorrow + Duration::hours(8),
+            ..Default::default()
+        };
+        let reminders = [daily, once];
+        let occurrences = agenda::occurrences(&reminders, Span::Days(3), now);
+        let titles = occurrences
+            .iter()
+            .map(|(_, reminder, skipped)| (reminder.title.as_str(), *skipped))
+            .collect::<Vec<_>>();
+        assert_eq!(titles, [("once", false), ("daily", true), ("daily", false)]);
+    }
+
+    #[test]
+    fn test_relative_phrases() {
+        let config = Config::default();
+        let reminder = |args: &[&str]| {
+            parse_reminder(&tokens(args, &config).unwrap(), &config)
+                .unwrap()
+                .reminder
+        };
+
+        // in/after N units
+        assert_eq!(
+            tokens(&["in", "2", "hours", "tea"], &config).unwrap(),
+            [
+                Arg::Number(2),
+                Arg::TimeUnit(TimeUnit::Hour(0)),
+                title("tea")
+            ]
+        );
+        assert_eq!(reminder(&["after", "3", "days", "x"]).interval.days, 3);
+        assert_eq!(reminder(&["in", "2h", "x"]).interval.hours, 2);
+        assert_eq!(reminder(&["in", "a", "week", "x"]).interval.days, 7);
+        assert_eq!(
+            tokens(&["meeting", "in", "office"], &config).unwrap(),
+            [title("meeting in office")]
+        );
+        assert_eq!(
+            tokens(&["after", "party"], &config).unwrap(),
+            [title("after party")]
+        );
+
+        // every other/Nth unit, number is the step rather than repeat count
+        let every = |args: &[&str]| {
+            let reminder = reminder(args);
+            assert_eq!(reminder.repeats, 0);
+            reminder.interval.days
+        };
+        assert_eq!(every(&["every", "other", "day", "x"]), 2);
+        assert_eq!(every(&["every", "3rd", "day", "x"]), 3);
+        assert_eq!(every(&["every", "3", "days", "x"]), 3);
+        assert_eq!(every(&["every", "two", "weeks", "x"]), 14);
+        assert_eq!(every(&["every", "next", "day", "x"]), 1);
+        assert_eq!(reminder(&["repeat", "3", "x"]).repeats, 3);
+        assert_eq!(
+            tokens(&["call", "other", "guy"], &config).unwrap(),
+            [title("call other guy")]
+        );
+
+        // this is the coming weekday, next the one a week after
+        let today = Local::now().date_naive();
+        let friday = reminder(&["this", "friday", "x"]).end_time.date_naive();
+        assert_eq!(friday.weekday(), chrono::Weekday::Fri);
+        assert!((1..=7).contains(&(friday - today).num_days()));
+        assert_eq!(reminder(&["friday", "x"]).end_time.date_naive(), friday);
+        let next_friday = reminder(&["next", "friday", "x"]).end_time.date_naive();
+        assert_eq!((next_friday - friday).num_days(), 7);
+        assert_eq!(
+            tokens(&["this", "thing"], &config).unwrap(),
+            [title("this thing")]
+        );
+        assert_eq!(
+            tokens(&["next", "kq27"], &config).unwrap(),
+            [Arg::Next(0), title("kq27")]
+        );
+
+        // the day after tomorrow
+        let reminder = reminder(&["the", "day", "after", "tomorrow", "x"]);
+        assert_eq!(reminder.interval.days, 2);
+        assert_eq!(reminder.title, "x");
+        assert_eq!(
+            tokens(&["call", "the", "bank"], &config).unwrap(),
+            [title("call the bank")]
+        );
+    }
+
+    #[test]
+    fn test_day_parts() {
+        let config = Config::default();
+        assert_eq!(
+            tokens(&["tonight", "call", "mom"], &config).unwrap(),
+            [Arg::Time(21, 0, 0), title("call mom")]
+        );
+        assert_eq!(
+            tokens(&["tomorrow", "morning", "x"], &config).unwrap(),
+            [
+                Arg::TimeUnit(TimeUnit::Day(1)),
+                Arg::Time(9, 0, 0),
+                title("x")
+            ]
+        );
+        assert_eq!(
+            tokens(&["friday", "evening", "x"], &config).unwrap(),
+            [Arg::WeekDay(FRIDAY), Arg::Time(19, 0, 0), title("x")]
+        );
+        assert_eq!(
+            tokens(&["this", "afternoon", "x"], &config).unwrap(),
+            [Arg::Time(15, 0, 0), title("x")]
+        );
+        assert_eq!(tokens(&["noon"], &config).unwrap(), [Arg::Time(12, 0, 0)]);
+        assert_eq!(
+            tokens(&["midnight"], &config).unwrap(),
+            [Arg::Time(0, 0, 0)]
+        );
+        assert_eq!(tokens(&["eod"], &config).unwrap(), [Arg::Time(17, 0, 0)]);
+        // no prefixes, "even" is not evening
+        assert_eq!(
+            tokens(&["even", "more"], &config).unwrap(),
+            [title("even more")]
+        );
+        // with another time only next to a date, otherwise part of title
+        assert_eq!(
+            tokens(&["5m", "morning", "run"], &config).unwrap(),
+            [Arg::TimeUnit(TimeUnit::Minute(5)), title("morning run")]
+        );
+        assert_eq!(
+            tokens(&["5pm", "midnight", "snack"], &config).unwrap(),
+            [Arg::Time(17, 0, 0), title("midnight snack")]
+        );
+        assert_eq!(
+            tokens(&["dec", "24", "evening", "party"], &config).unwrap(),
+            [
+                Arg::Month(11),
+                Arg::Number(24),
+                Arg::Time(19, 0, 0),
+                title("party")
+            ]
+        );
+        assert_eq!(
+            tokens(&["christmas", "eve"], &config).unwrap(),
+            [title("christmas eve")]
+        );
+
+        // midnight that already passed today is tomorrow's
+        let parsed =
+            parse_reminder(&tokens(&["midnight", "x"], &config).unwrap(), &config).unwrap();
+        let tomorrow = Local::now().date_naive().succ_opt().unwrap();
+        assert_eq!(parsed.reminder.end_time.date_naive(), tomorrow);
+
+        let config: Config = "[times]\nmorning = \"7:30\"\neod = \"6pm\""
+            .parse()
+            .unwrap();
+        assert_eq!(
+            tokens(&["morning"], &config).unwrap(),
+            [Arg::Time(7, 30, 0)]
+        );
+        assert_eq!(tokens(&["eod"], &config).unwrap(), [Arg::Time(18, 0, 0)]);
+        assert!(config.vocab.help().contains("morning (7:30)"));
+        assert!("[times]\nlunch = \"12:30\"".parse::<Config>().is_err());
+        assert!("[times]\nnoon = \"soon\"".parse::<Config>().is_err());
+    }
+
+    #[test]
+    fn test_dates() {
+        let config = Config::default();
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+
+        // iso 8601
+        assert_eq!(
+            tokens(&["2029-02-28", "x"], &config).unwrap(),
+            [Arg::Date(date(2029, 2, 28)), title("x")]
+        );
+        assert_eq!(
+            tokens(&["2029-02-28T09:00"], &config).unwrap(),
+            [Arg::Date(date(2029, 2, 28)), Arg::Time(9, 0, 0)]
+        );
+        assert_eq!(
+            tokens(&["2029-02-28t09:00:15.250"], &config).unwrap(),
+            [Arg::Date(date(2029, 2, 28)), Arg::Time(9, 0, 15)]
+        );
+        // offsets are moved to local time
+        let utc = date(2029, 2, 28).and_hms_opt(7, 0, 0).unwrap().and_utc();
+        let local = utc.with_timezone(&Local).naive_local();
+        let expected = [
+            Arg::Date(local.date()),
+            Arg::Time(local.hour(), local.minute(), 0),
+        ];
+        for arg in [
+            "2029-02-28T07:00Z",
+            "2029-02-28T09:00+02:00",
+            "2029-02-28T02:30-0430",
+        ] {
+            assert_eq!(tokens(&[arg], &config).unwrap(), expected);
+        }
+        assert!(tokens(&["2029-02-28T25:00"], &config).is_err());
+        let error = tokens(&["2029-02-29"], &config).unwrap_err();
+        assert_eq!(error.exit_code(), 3);
+        assert!(error.to_string().contains("did you mean feb 28 2029?"));
+
+        // year given, once
+        let parsed = parse_reminder(&tokens(&["2029-02-28", "x"], &config).unwrap(), &config);
+        let reminder = parsed.unwrap().reminder;
+        assert_eq!(reminder.end_time.date_naive(), date(2029, 2, 28));
+        assert_eq!(reminder.repeats, 1);
+        let past = tokens(&["2020-01-01", "x"], &config).unwrap();
+        assert!(parse_reminder(&past, &config).is_err());
+
+        // dd.mm[.yyyy]
+        let feb_28 = vec![Arg::Month(1), Arg::Number(28)];
+        assert_eq!(tokens(&["28.02"], &config).unwrap(), feb_28);
+        assert_eq!(
+            tokens(&["28.02.29"], &config).unwrap(),
+            [Arg::Date(date(2029, 2, 28))]
+        );
+        assert_eq!(tokens(&["28.13"], &config).unwrap(), [title("28.13")]);
+        assert_eq!(tokens(&["1.5h"], &config).unwrap(), [title("1.5h")]);
+        assert!(tokens(&["2029-13-01"], &config).is_err());
+
+        // decimals, fractions and numbers after title text are part of it
+        let titles = [
+            &["read", "chapter", "3.2"][..],
+            &["buy", "1/2", "gallon", "milk"],
+            &["pay", "12.50"],
+            &["version", "2.0", "release"],
+            &["31.2", "percent"],
+        ];
+        for args in titles {
+            assert_eq!(tokens(args, &config).unwrap(), [title(&args.join(" "))]);
+        }
+
+        // mm/dd, dd/mm when config says so or month can't come first
+        assert_eq!(tokens(&["02/28"], &config).unwrap(), feb_28);
+        assert_eq!(tokens(&["28/02"], &config).unwrap(), feb_28);
+        assert_eq!(
+            tokens(&["02/03/2029"], &config).unwrap(),
+            [Arg::Date(date(2029, 2, 3))]
+        );
+        let day_first: Config = "[defaults]\ndate-order = \"dmy\"".parse().unwrap();
+        assert_eq!(
+            tokens(&["02/03/2029"], &day_first).unwrap(),
+            [Arg::Date(date(2029, 3, 2))]
+        );
+        assert_eq!(tokens(&["02/28"], &day_first).unwrap(), feb_28);
+        assert!("[defaults]\ndate-order = \"ymd\""
+            .parse::<Config>()
+            .is_err());
+
+        // day before month, ordinals next to a month
+        assert_eq!(tokens(&["28", "feb"], &config).unwrap(), feb_28);
+        assert_eq!(tokens(&["28th", "feb"], &config).unwrap(), feb_28);
+        assert_eq!(tokens(&["feb", "28th"], &config).unwrap(), feb_28);
+        assert_eq!(
+            tokens(&["28", "feb", "2029"], &config).unwrap(),
+            [Arg::Month(1), Arg::Number(28), Arg::Number(2029)]
+        );
+        assert_eq!(
+            tokens(&["3", "feb", "28"], &config).unwrap(),
+            [Arg::Number(3), Arg::Month(1), Arg::Number(28)]
+        );
+        assert_eq!(
+            tokens(&["3rd", "floor"], &config).unwrap(),
+            [title("3rd floor")]
+        );
+    }
+
+    #[test]
+    fn test_durations() {
+        let config = Config::default();
+        let units = |arg: &str| {
+            tokens(&[arg], &config)
+                .unwrap()
+                .into_iter()
+                .map(|tok| match tok {
+                    Arg::TimeUnit(unit) => unit,
+                    tok => panic!("{tok:?} is not a time unit"),
+                })
+                .collect::<Vec<_>>()
+        };
+        use TimeUnit::*;
+        assert_eq!(units("1h30m"), [Hour(1), Minute(30)]);
+        assert_eq!(units("2d4h15m"), [Day(2), Hour(4), Minute(15)]);
+        assert_eq!(units("1w2d"), [Week(1), Day(2)]);
+        assert_eq!(units("1y6mo"), [Year(1), Month(6)]);
+        assert_eq!(units("1h0m"), [Hour(1)]);
+        // iso 8601, m is months before T
+        assert_eq!(units("PT1H30M"), [Hour(1), Minute(30)]);
+        assert_eq!(units("p1dt12h"), [Day(1), Hour(12)]);
+        assert_eq!(units("P1Y2M3W"), [Year(1), Month(2), Week(3)]);
+        assert_eq!(units("PT1M"), [Minute(1)]);
+        // anything short of a whole iso duration is a word
+        for arg in ["P1", "pt2", "P1H", "PT1H30", "P1.5D"] {
+            assert_eq!(tokens(&[arg], &config).unwrap(), [title(arg)]);
+        }
+        assert_eq!(
+            tokens(&["5m", "fix", "P1", "bug"], &config).unwrap(),
+            [Arg::TimeUnit(Minute(5)), title("fix P1 bug")]
+        );
+
+        // summed into interval
+        let reminder = |args: &[&str]| {
+            let tokens = tokens(args, &config).unwrap();
+            parse_reminder(&tokens, &config).unwrap().reminder
+        };
+        let interval = reminder(&["every", "1h30m", "stretch"]).interval;
+        assert_eq!((interval.hours, interval.mins), (1, 30));
+        let reminder = reminder(&["in", "2d4h", "x"]);
+        assert_eq!((reminder.interval.days, reminder.interval.hours), (2, 4));
+        assert_eq!(reminder.title, "x");
+
+        // ambiguous or broken mixes
+        for arg in ["1h30", "30m1h", "1h2h", "1y6m", "0h0m"] {
+            let error = tokens(&[arg], &config).unwrap_err();
+            assert_eq!(error.exit_code(), 2, "{arg}");
+        }
+        assert!(tokens(&["1h30"], &config)
+            .unwrap_err()
+            .to_string()
+            .contains("did you mean 1h30m?"));
+        // not durations at all
+        assert_eq!(units("3d"), [Day(3)]);
+        assert_eq!(tokens(&["2x4"], &config).unwrap(), [Arg::Repeat(4)]);
+        assert_eq!(
+            tokens(&["pt", "3rd"], &config).unwrap(),
+            [Arg::Title("pt 3rd".to_owned())]
+        );
+    }
+
+    #[test]
+    fn test_window() {
+        let config = Config::default();
+        let time = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        let args = [
+            "every", "30", "minutes", "between", "9am", "and", "5pm", "on", "workdays", "stretch",
+        ];
+        assert_eq!(
+            tokens(&args, &config).unwrap(),
+            [
+                Arg::Repeat(0),
+                Arg::TimeUnit(TimeUnit::Minute(30)),
+                Arg::Window(time(9, 0), time(17, 0)),
+                Arg::WeekDay(MONDAY | TUESDAY | WEDNESDAY | THURSDAY | FRIDAY),
+                Arg::Title("stretch".to_owned()),
+            ]
+        );
+        assert_eq!(
+            tokens(&["from", "22:00", "to", "6am"], &config).unwrap(),
+            [Arg::Window(time(22, 0), time(6, 0))]
+        );
+        assert_eq!(
+            tokens(&["between", "9am", "and", "12pm"], &config).unwrap(),
+            [Arg::Window(time(9, 0), time(12, 0))]
+        );
+        assert_eq!(
+            tokens(&["from", "12am", "to", "1am"], &config).unwrap(),
+            [Arg::Window(time(0, 0), time(1, 0))]
+        );
+        assert_eq!(
+            tokens(&["talk", "between", "us"], &config).unwrap(),
+            [Arg::Title("talk between us".to_owned())]
+        );
+        assert!(tokens(&["between", "9am", "x"], &config).is_err());
+        assert!(tokens(&["between", "9am", "and", "9:00"], &config).is_err());
+        let parsed = parse_reminder(&tokens(&args, &config).unwrap(), &config).unwrap();
+        assert_eq!(parsed.reminder.window, Some((time(9, 0), time(17, 0))));
+        // only narrows a repeat interval, edit can set it alone
+        for args in [
+            &["between", "9am", "and", "5pm", "x"][..],
+            &["5m", "between", "9am", "and", "5pm", "x"],
+            &["FREQ=HOURLY", "between", "9am", "and", "5pm", "x"],
+            &["cron", "0 * * * *", "between", "9am", "and", "5pm", "x"],
+        ] {
+            let tokens = tokens(args, &config).unwrap();
+            let error = parse_reminder(&tokens, &config).err();
+            assert_eq!(error.map(|e| e.exit_code()), Some(2), "{args:?}");
+        }
+        let window_only = || {
+            let tokens = tokens(&["from", "9am", "to", "5pm"], &config).unwrap();
+            parse_reminder(&tokens, &config).unwrap()
+        };
+        assert!(window_only().window);
+        // edit checks the reminder window ends up on
+        let hourly = Reminder {
+            interval: Interval {
+                hours: 1,
+                ..Default::default()
+            },
+            end_time: Local::now() + Duration::hours(1),
+            repeats: 0,
+            ..Default::default()
+        };
+        let mut reminder = hourly.clone();
+        window_only().apply(&mut reminder).unwrap();
+        assert_eq!(reminder.window, Some((time(9, 0), time(17, 0))));
+        let once = Reminder {
+            repeats: 1,
+            ..hourly.clone()
+        };
+        let rrule = Reminder {
+            rrule: Some("FREQ=HOURLY".parse().unwrap()),
+            ..hourly.clone()
+        };
+        let cron = Reminder {
+            cron: Some("0 * * * *".parse().unwrap()),
+            ..hourly
+        };
+        for mut reminder in [once, rrule, cron] {
+            assert!(window_only().apply(&mut reminder).is_err());
+        }
+
+        // jumps to next window start, weekdays still apply
+        let at =
+            |date: &str| local_time(NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M").unwrap());
+        let reminder = Reminder {
+            interval: Interval {
+                mins: 30,
+                ..Default::default()
+            },
+            end_time: at("2029-03-01 16:45"),
+            repeats: 0,
+            weekdays: MONDAY | TUESDAY | WEDNESDAY | THURSDAY | FRIDAY,
+            window: Some((time(9, 0), time(17, 0))),
+            ..Default::default()
+        };
+        let times = |reminder: &Reminder| {
+            reminder
+                .occurrences()
+                .take(4)
+                .map(|(time, _)| time)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            times(&reminder),
+            [
+                at("2029-03-01 16:45"),
+                at("2029-03-02 09:00"),
+                at("2029-03-02 09:30"),
+                at("2029-03-02 10:00")
+            ]
+        );
+        let friday = Reminder {
+            end_time: at("2029-03-02 17:00"),
+            ..reminder.clone()
+        };
+        assert_eq!(times(&friday)[1], at("2029-03-05 09:00"));
+        // window across midnight
+        let night = Reminder {
+            interval: Interval {
+                hours: 2,
+                ..Default::default()
+            },
+            end_time: at("2029-03-01 04:00"),
+            weekdays: 0,
+            window: Some((time(22, 0), time(6, 0))),
+            ..reminder.clone()
+        };
+        assert_eq!(
+            times(&night),
+            [
+                at("2029-03-01 04:00"),
+                at("2029-03-01 06:00"),
+                at("2029-03-01 22:00"),
+                at("2029-03-02 00:00")
+            ]
+        );
+
+        // update moves reminder due now into its window
+        let now = Local::now();
+        let start = (now + Duration::hours(3)).time().with_second(0).unwrap();
+        let end = start + Duration::hours(1);
+        let mut reminder = Reminder {
+            end_time: now - Duration::minutes(10),
+            weekdays: 0,
+            window: Some((start, end)),
+            ..reminder
+        };
+        reminder.update();
+        let today = local_time(now.date_naive().and_time(start));
+        let expected = match today > now {
+            true => today,
+            false => local_time(now.date_naive().succ_opt().unwrap().and_time(start)),
+        };
+        assert_eq!(reminder.end_time, expected);
+    }
 }
@@ -1,13 +1,24 @@
-use crate::Interval;
-use chrono::{DateTime, Datelike, Duration, Local, Months, NaiveDateTime, TimeZone};
+use crate::{CatchUp, Cron, Interval, RRule};
+use chrono::{DateTime, Datelike, Duration, Local, Months, NaiveDateTime, NaiveTime, TimeZone};
 
+#[derive(Clone)]
 pub struct Reminder {
+    // short persistent id, assigned when reminder is added
+    pub id: String,
     pub title: String,
     pub interval: Interval,
     pub end_time: DateTime<Local>,
     pub repeats: u32,
     pub skips: u32,
     pub weekdays: u8,
+    // None uses the global catch-up policy
+    pub catch_up: Option<CatchUp>,
+    // recurrence rule, replaces interval and weekdays when set
+    pub rrule: Option<RRule>,
+    // crontab schedule, same as rrule
+    pub cron: Option<Cron>,
+    // (start, end) time of day it may fire in, both inclusive, 22:00-6:00 spans midnight
+    pub window: Option<(NaiveTime, NaiveTime)>,
 }
 
 pub const SUNDAY: u8 = 0b0000001;
@@ -18,6 +29,96 @@ pub const THURSDAY: u8 = 0b0010000;
 pub const FRIDAY: u8 = 0b0100000;
 pub const SATURDAY: u8 = 0b1000000;
 
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+const WINDOW_FORMAT: &str = "%H:%M:%S";
+
+// ids are two of these letters and digits, too short for vocab prefix matches (4+ letters)
+// and no two letter vocab name uses only these letters, so ids always tokenize as titles
+const ID_LETTERS: &[u8] = b"bcgjkqvxz";
+
+// short random id like "kq27", `taken` rejects ids that are already in use
+pub fn generate_id(taken: impl Fn(&str) -> bool) -> String {
+    use std::hash::{BuildHasher, RandomState};
+    let state = RandomState::new();
+    for attempt in 0u64.. {
+        let n = state.hash_one(attempt) as usize;
+        let letter = |i: usize| ID_LETTERS[i % ID_LETTERS.len()] as char;
+        // more digits once short ids get crowded
+        let digits = 2 + attempt as usize / 1000;
+        let number = n / 81 % 10usize.pow(digits as u32);
+        let id = format!("{}{}{number:0digits$}", letter(n), letter(n / 9));
+        if !taken(&id) {
+            return id;
+        }
+    }
+    unreachable!()
+}
+
+fn weekdays_from_str(str: &str) -> Result<u8, String> {
+    let mut bits = 0;
+    for day in str.split(' ').filter(|day| !day.is_empty()) {
+        bits |= match day {
+            "sun" => SUNDAY,
+            "mon" => MONDAY,
+            "tue" => TUESDAY,
+            "wed" => WEDNESDAY,
+            "thu" => THURSDAY,
+            "fri" => FRIDAY,
+            "sat" => SATURDAY,
+            _ => return Err(format!("invalid weekday \"{day}\"")),
+        }
+    }
+    Ok(bits)
+}
+
+fn window_from_str(str: &str) -> Result<(NaiveTime, NaiveTime), String> {
+    let invalid = || format!("invalid window \"{str}\"");
+    let (start, end) = str.split_once('-').ok_or_else(invalid)?;
+    let time = |time| NaiveTime::parse_from_str(time, WINDOW_FORMAT).map_err(|_| invalid());
+    Ok((time(start)?, time(end)?))
+}
+
+fn parse_time(str: &str, format: &str) -> Result<DateTime<Local>, String> {
+    NaiveDateTime::parse_from_str(str, format)
+        .map(local_time)
+        .map_err(|_| format!("invalid time \"{str}\""))
+}
+
+// makes value safe to store between tabs on a single line
+pub fn escape(str: &str) -> String {
+    let mut escaped = String::with_capacity(str.len());
+    for c in str.chars() {
+        match c {
+            '\\' => escaped += "\\\\",
+            '\t' => escaped += "\\t",
+            '\n' => escaped += "\\n",
+            '\r' => escaped += "\\r",
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+pub fn unescape(str: &str) -> Result<String, String> {
+    let mut unescaped = String::with_capacity(str.len());
+    let mut chars = str.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        unescaped.push(match chars.next() {
+            Some('\\') => '\\',
+            Some('t') => '\t',
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some(c) => return Err(format!("invalid escape \"\\{c}\"")),
+            None => return Err("dangling \\ at end of value".to_string()),
+        });
+    }
+    Ok(unescaped)
+}
+
 impl Reminder {
     pub fn weekdays_to_str(&self) -> String {
         let bits = if self.weekdays == 0 {
@@ -37,54 +138,132 @@ impl Reminder {
         weekdays
     }
 
+    // one line of tab separated key=value fields, values are escaped
     pub fn serialize(&self) -> String {
-        format!(
-            "{}⌠{}⌠{}⌠{}⌠{}⌠{}\n",
-            self.title,
-            self.interval.serialize(),
-            self.end_time.format("%y-%m-%d %H:%M:%S"),
-            self.repeats,
-            self.skips,
-            self.weekdays_to_str()
-        )
+        let mut fields = vec![
+            ("id", self.id.clone()),
+            ("title", self.title.clone()),
+            ("interval", self.interval.serialize()),
+            ("end", self.end_time.format(TIME_FORMAT).to_string()),
+            ("repeats", self.repeats.to_string()),
+            ("skips", self.skips.to_string()),
+            ("weekdays", self.weekdays_to_str()),
+        ];
+        if let Some(catch_up) = self.catch_up {
+            fields.push(("catch-up", catch_up.to_string()));
+        }
+        if let Some(rrule) = &self.rrule {
+            fields.push(("rrule", rrule.to_string()));
+        }
+        if let Some(cron) = &self.cron {
+            fields.push(("cron", cron.to_string()));
+        }
+        if let Some((start, end)) = self.window {
+            let (start, end) = (start.format(WINDOW_FORMAT), end.format(WINDOW_FORMAT));
+            fields.push(("window", format!("{start}-{end}")));
+        }
+        let fields: Vec<String> = fields
+            .into_iter()
+            .map(|(key, value)| format!("{key}={}", escape(&value)))
+            .collect();
+        fields.join("\t") + "\n"
     }
 
-    pub fn deserialize(line: &str) -> Self {
-        let data: Vec<&str> = line.split('⌠').collect();
-        let weekdays = data[5].split(" ");
-        let mut weekday_bits = 0;
-        for day in weekdays {
-            weekday_bits |= match day {
-                "sun" => SUNDAY,
-                "mon" => MONDAY,
-                "tue" => TUESDAY,
-                "wed" => WEDNESDAY,
-                "thu" => THURSDAY,
-                "fri" => FRIDAY,
-                "sat" => SATURDAY,
-                _ => {
-                    eprintln!("invalid weekday pattern encountered while parsing reminders.txt");
-                    0
+    fn set_field(&mut self, key: &str, value: String) -> Result<(), String> {
+        let number = |value: &str| {
+            value
+                .parse::<u32>()
+                .map_err(|_| format!("invalid {key} \"{value}\""))
+        };
+        match key {
+            "id" => self.id = value,
+            "title" => self.title = value,
+            "interval" => self.interval = Interval::deserialize(&value)?,
+            "end" => self.end_time = parse_time(&value, TIME_FORMAT)?,
+            "repeats" => self.repeats = number(&value)?,
+            "skips" => self.skips = number(&value)?,
+            "weekdays" => self.weekdays = weekdays_from_str(&value)?,
+            "catch-up" => self.catch_up = Some(value.parse()?),
+            "rrule" => self.rrule = Some(value.parse()?),
+            "cron" => self.cron = Some(value.parse()?),
+            "window" => self.window = Some(window_from_str(&value)?),
+            _ => return Err(format!("unknown field \"{key}\"")),
+        }
+        Ok(())
+    }
+
+    pub fn deserialize(line: &str) -> Result<Self, String> {
+        let mut reminder = Self::default();
+        let mut keys = Vec::new();
+        for field in line.split('\t') {
+            let (key, value) = field
+                .split_once('=')
+                .ok_or(format!("field \"{field}\" is missing '='"))?;
+            reminder.set_field(key, unescape(value)?)?;
+            keys.push(key);
+        }
+        if !keys.contains(&"title") {
+            return Err("missing title".to_string());
+        }
+        if !keys.contains(&"end") {
+            return Err("missing end time".to_string());
+        }
+        Ok(reminder)
+    }
+
+    // best effort recovery of a line that failed to parse, in either format
+    // keeps every field that is still valid, needs at least a title
+    pub fn repair(line: &str) -> Option<Self> {
+        let mut reminder = Self::default();
+        if line.contains('⌠') {
+            const KEYS: [&str; 9] = [
+                "title", "interval", "end", "repeats", "skips", "weekdays", "catch-up", "rrule",
+                "cron",
+            ];
+            for (key, value) in KEYS.iter().zip(line.split('⌠')) {
+                let value = match *key {
+                    // v1 used 2 digit years
+                    "end" => NaiveDateTime::parse_from_str(value, "%y-%m-%d %H:%M:%S")
+                        .map(|time| time.format(TIME_FORMAT).to_string())
+                        .unwrap_or_default(),
+                    _ => value.to_owned(),
+                };
+                reminder.set_field(key, value).unwrap_or_default();
+            }
+        } else {
+            for field in line.split('\t') {
+                if let Some((key, value)) = field.split_once('=') {
+                    let value = unescape(value).unwrap_or(value.to_owned());
+                    reminder.set_field(key, value).unwrap_or_default();
                 }
             }
         }
-        Self {
+        (!reminder.title.trim().is_empty()).then_some(reminder)
+    }
+
+    // original format: title⌠interval⌠end⌠repeats⌠skips⌠weekdays[⌠catch-up⌠rrule⌠cron]
+    pub fn deserialize_v1(line: &str) -> Result<Self, String> {
+        let data: Vec<&str> = line.split('⌠').collect();
+        if data.len() < 6 {
+            return Err(format!("expected at least 6 fields, found {}", data.len()));
+        }
+        let optional = |i: usize| data.get(i).map(|s| s.trim()).filter(|s| !s.is_empty());
+        Ok(Self {
+            id: String::new(),
             title: data[0].to_owned(),
-            interval: Interval::deserialize(data[1]),
-            end_time: Local
-                .from_local_datetime(
-                    &NaiveDateTime::parse_from_str(data[2], "%y-%m-%d %H:%M:%S")
-                        .unwrap_or_default(),
-                )
-                .single()
-                .unwrap_or_default(),
+            interval: Interval::deserialize(data[1])?,
+            end_time: parse_time(data[2], "%y-%m-%d %H:%M:%S")?,
             repeats: data[3].parse().unwrap_or_default(),
             skips: data[4].parse().unwrap_or_default(),
-            weekdays: weekday_bits,
-        }
+            weekdays: weekdays_from_str(data[5])?,
+            catch_up: optional(6).map(|c| c.parse()).transpose()?,
+            rrule: optional(7).map(|r| r.parse()).transpose()?,
+            cron: optional(8).map(|c| c.parse()).transpose()?,
+            window: None,
+        })
     }
 
-    fn weekdays_match_end_weekday(&self) -> bool {
+    pub fn weekdays_match_end_weekday(&self) -> bool {
         let mut weekdays = self.weekdays;
         if weekdays == 0 {
             weekdays = u8::MAX;
@@ -102,41 +281,168 @@ impl Reminder {
         matches > 0
     }
 
+    // window narrows a repeat interval, rrule and cron pick their own times and a one-off has none
+    pub fn window_error(&self) -> Option<&'static str> {
+        self.window?;
+        if self.rrule.is_some() || self.cron.is_some() {
+            return Some("window can't be combined with rrule or cron");
+        }
+        if self.repeats == 1 || self.interval.is_zero() {
+            return Some("window needs a reminder that repeats");
+        }
+        None
+    }
+
+    // moves end time outside of window to the next window start
+    fn align_window(&mut self) {
+        let Some((start, end)) = self.window else {
+            return;
+        };
+        if self.rrule.is_some() || self.cron.is_some() {
+            return;
+        }
+        let time = self.end_time.time();
+        let inside = match start <= end {
+            true => start <= time && time <= end,
+            false => start <= time || time <= end,
+        };
+        if inside {
+            return;
+        }
+        let mut date = self.end_time.date_naive();
+        // past today's window, next one starts tomorrow
+        if start <= end && time > end {
+            date = date.succ_opt().unwrap_or(date);
+        }
+        self.end_time = local_time(date.and_time(start));
+    }
+
+    fn align_weekday(&mut self) {
+        if self.rrule.is_some() || self.cron.is_some() {
+            return;
+        }
+        while !self.weekdays_match_end_weekday() {
+            self.end_time += Duration::days(1);
+        }
+    }
+
+    // moves end time to the next occurrence
+    // returns false if there are no more occurrences
+    fn advance(&mut self) -> bool {
+        let end_time = self.end_time.naive_local();
+        let next = match (&self.rrule, &self.cron) {
+            (Some(rrule), _) => Some(rrule.next_after(end_time)),
+            (_, Some(cron)) => Some(cron.next_after(end_time)),
+            _ => None,
+        };
+        if let Some(next) = next {
+            return match next {
+                Some(next) => {
+                    self.end_time = local_time(next);
+                    true
+                }
+                None => false,
+            };
+        }
+        // feb 29 + 1 year is feb 28
+        let months = self.interval.years.saturating_mul(12);
+        let months = months.saturating_add(self.interval.months);
+        let delta = Duration::days(self.interval.days as i64)
+            + Duration::hours(self.interval.hours as i64)
+            + Duration::minutes(self.interval.mins as i64)
+            + Duration::seconds(self.interval.secs as i64);
+        // past the last representable time there are no more occurrences
+        let next = self
+            .end_time
+            .checked_add_months(Months::new(months))
+            .and_then(|time| time.checked_add_signed(delta));
+        let Some(next) = next else {
+            return false;
+        };
+        self.end_time = next;
+        self.align_window();
+        self.align_weekday();
+        true
+    }
+
+    // upcoming fire times from end time on, paired with whether that one is skipped
+    // ends with last repeat, or never for reminders that repeat forever
+    pub fn occurrences(&self) -> impl Iterator<Item = (DateTime<Local>, bool)> {
+        let mut reminder = self.clone();
+        let mut done = false;
+        std::iter::from_fn(move || {
+            if done {
+                return None;
+            }
+            let time = reminder.end_time;
+            let skipped = reminder.skips > 0;
+            reminder.skips = reminder.skips.saturating_sub(1);
+            if reminder.repeats > 0 {
+                reminder.repeats -= 1;
+                done = reminder.repeats == 0;
+            }
+            // reminders without interval would fire at the same time forever
+            done = done || !reminder.advance() || reminder.end_time <= time;
+            Some((time, skipped))
+        })
+    }
+
     // updates repeating reminder's end time so that remind time is not up anymore
-    // returns (updated, should_remove)
-    pub fn update(&mut self) -> (bool, bool) {
+    // returns (occurrences that came due, should_remove)
+    pub fn update(&mut self) -> (u32, bool) {
         let now = Local::now();
-        let mut updated = false;
+        let mut due = 0;
         let always_repeats = self.repeats == 0;
         while self.end_time <= now {
-            updated = true;
+            due += 1;
             if !always_repeats {
                 self.repeats -= 1;
                 if self.repeats == 0 {
-                    return (updated, true);
+                    return (due, true);
                 }
             }
-            self.end_time = self
-                .end_time
-                .with_year(self.end_time.year() + self.interval.years as i32)
-                .unwrap();
-            self.end_time = self
-                .end_time
-                .checked_add_months(Months::new(self.interval.months))
-                .unwrap();
-            self.end_time += Duration::days(self.interval.days as i64);
-            self.end_time += Duration::hours(self.interval.hours as i64);
-            self.end_time += Duration::minutes(self.interval.mins as i64);
-            self.end_time += Duration::seconds(self.interval.secs as i64);
-        }
-        while !self.weekdays_match_end_weekday() {
-            self.end_time += Duration::days(1);
+            if !self.advance() {
+                return (due, true);
+            }
         }
+        self.align_window();
+        self.align_weekday();
         let should_remove = !always_repeats && self.repeats == 0;
-        (updated, should_remove)
+        (due, should_remove)
     }
 }
 
+impl Default for Reminder {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            title: String::new(),
+            interval: Interval::default(),
+            end_time: Local::now(),
+            repeats: 1,
+            skips: 0,
+            weekdays: 0,
+            catch_up: None,
+            rrule: None,
+            cron: None,
+            window: None,
+        }
+    }
+}
+
+// resolves local wall time, times skipped by DST move forward
+pub fn local_time(time: NaiveDateTime) -> DateTime<Local> {
+    Local
+        .from_local_datetime(&time)
+        .earliest()
+        .or_else(|| {
+            Local
+                .from_local_datetime(&(time + Duration::hours(1)))
+                .earliest()
+        })
+        .unwrap_or_default()
+}
+
 impl std::fmt::Display for Reminder {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let now = Local::now();
@@ -185,7 +491,11 @@ impl std::fmt::Display for Reminder {
             1 => " [once]".to_string(),
             n => format!(" [{n} times]"),
         };
-        let interval_str = if self.repeats == 0 || self.repeats > 1 {
+        let interval_str = if let Some(rrule) = &self.rrule {
+            format!(" [{rrule}]")
+        } else if let Some(cron) = &self.cron {
+            format!(" [cron {cron}]")
+        } else if self.repeats == 0 || self.repeats > 1 {
             match self.interval {
                 Interval {
                     secs: 0,
@@ -268,12 +578,25 @@ impl std::fmt::Display for Reminder {
         } else {
             format!(" [skip {} times]", self.skips)
         };
+        let catch_up = self
+            .catch_up
+            .map(|c| format!(" [catch-up {c}]"))
+            .unwrap_or_default();
+        let window = self
+            .window
+            .map(|(start, end)| format!(" [{}-{}]", start.format("%-H:%M"), end.format("%-H:%M")))
+            .unwrap_or_default();
         let mut end = self.end_time.format("%y-%m-%d %H:%M:%S").to_string();
         if end.ends_with(":00") {
             end = end[..end.len() - 3].to_string();
         }
+        let id = if self.id.is_empty() {
+            String::new()
+        } else {
+            format!("{} ", self.id)
+        };
         f.write_fmt(format_args!(
-            "\"{title}\"{skip}{repeat}{weekdays}{interval_str} [{end}]{due_str}"
+            "{id}\"{title}\"{skip}{repeat}{weekdays}{interval_str}{window}{catch_up} [{end}]{due_str}"
         ))
     }
 }
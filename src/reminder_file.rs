@@ -1,6 +1,11 @@
-use crate::Reminder;
-use chrono::Local;
-use notify::{EventKind, RecursiveMode, Watcher};
+use crate::{
+    agenda::{self, Span},
+    generate_id,
+    journal::{self, Actor, Change, Entry, Journal, Op, Retention},
+    CatchUp, Error, Reminder,
+};
+use chrono::{Duration, Local};
+use notify::EventKind;
 use std::{fs::OpenOptions, io::Write, path::Path};
 
 fn fuzzy_score(match_str: &str, search_str: &str) -> usize {
@@ -30,84 +35,539 @@ fn fuzzy_score(match_str: &str, search_str: &str) -> usize {
     score
 }
 
+// how many fire times next, list --expand and explain show by default
+pub const UPCOMING: usize = 5;
+
+// upcoming fire times of reminder, one per line
+pub fn print_occurrences(reminder: &Reminder, count: usize) {
+    for (time, skipped) in reminder.occurrences().take(count) {
+        let skipped = if skipped { " (skipped)" } else { "" };
+        println!("  {}{skipped}", time.format("%a %y-%m-%d %H:%M:%S"));
+    }
+}
+
+// advisory lock shared by cli and daemon, released on drop
+pub struct FileLock(std::fs::File);
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        self.0.unlock().unwrap_or_default();
+    }
+}
+
+pub struct ParseError {
+    pub line: usize,
+    pub text: String,
+    pub reason: String,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
+}
+
+// what doctor found, see diagnose
+pub struct Diagnosis {
+    // (reason, line) pairs from rejected sidecar file
+    pub rejected: Vec<(String, String)>,
+    // index and description of suspicious reminders
+    pub problems: Vec<(usize, String)>,
+}
+
+struct LoadedFile {
+    reminders: Vec<Reminder>,
+    // format version file was written in
+    version: u32,
+    errors: Vec<ParseError>,
+}
+
 pub struct ReminderFile {
     file: String,
-    history_dir: String,
+    journal: Journal,
+    // recorded as author of changes in journal
+    actor: Actor,
+    // log of reminders that came due while daemon was not running
+    missed_file: String,
+    // lines of reminders file that could not be parsed
+    rejected_file: String,
+    lock_file: String,
     reminders: Vec<Reminder>,
+    // name of list shown in notifications, None for default reminders file
+    label: Option<String>,
+    // seconds notifications stay on screen, None for system default
+    notification_timeout: Option<u32>,
+    // reminders before current change, see save_history
+    pending_history: Option<(Op, Vec<Reminder>)>,
+    catch_up: CatchUp,
 }
 
 impl ReminderFile {
-    const MAX_HISTORY: usize = 8;
+    // first line of reminders file, followed by format version
+    const HEADER: &'static str = "# remind reminders v";
+    const VERSION: u32 = 2;
 
-    pub fn new<P: AsRef<Path>>(file: P, history_dir: P) -> Self {
+    pub fn new<P: AsRef<Path>>(file: P) -> Self {
         Self {
             file: file.as_ref().to_string_lossy().to_string(),
-            history_dir: history_dir.as_ref().to_string_lossy().to_string(),
+            journal: Journal::new(file.as_ref().with_extension("journal")),
+            actor: Actor::Cli,
+            missed_file: file
+                .as_ref()
+                .with_extension("missed")
+                .to_string_lossy()
+                .to_string(),
+            rejected_file: file
+                .as_ref()
+                .with_extension("rejected")
+                .to_string_lossy()
+                .to_string(),
+            lock_file: file
+                .as_ref()
+                .with_extension("lock")
+                .to_string_lossy()
+                .to_string(),
             reminders: Vec::new(),
+            label: None,
+            notification_timeout: None,
+            pending_history: None,
+            catch_up: CatchUp::default(),
         }
     }
+
+    pub fn set_label(&mut self, label: &str) {
+        self.label = Some(label.to_owned());
+    }
+
+    pub fn set_notification_timeout(&mut self, secs: u32) {
+        self.notification_timeout = Some(secs);
+    }
+
+    pub fn set_history_retention(&mut self, retention: Retention) {
+        self.journal.set_retention(retention);
+    }
+
+    // policy for reminders that don't specify their own
+    pub fn set_catch_up(&mut self, catch_up: CatchUp) {
+        self.catch_up = catch_up;
+    }
     // appends directly to file
-    pub fn append(&self, reminder: &Reminder) {
-        let mut file = OpenOptions::new()
+    pub fn append(&mut self, mut reminder: Reminder) -> Result<(), Error> {
+        reminder.id = self.new_id();
+        self.reminders.push(reminder);
+        self.save()?;
+        println!("added: {}", self.reminders[self.reminders.len() - 1]);
+        Ok(())
+    }
+
+    // blocks until no other remind process (cli or daemon) is using reminders file
+    // hold it from load until save so concurrent changes are never lost
+    pub fn lock(&self) -> Result<FileLock, Error> {
+        let storage_error = |e| Error::storage(&self.lock_file, e);
+        let file = OpenOptions::new()
             .create(true)
-            .append(true)
-            .open(&self.file)
-            .unwrap();
-        file.write_all(reminder.serialize().as_bytes())
-            .expect("failed to add reminder");
-        println!("added: {reminder}");
+            .truncate(false)
+            .write(true)
+            .open(&self.lock_file)
+            .map_err(storage_error)?;
+        file.lock().map_err(storage_error)?;
+        Ok(FileLock(file))
     }
 
-    fn save_file(&self, file: &str) {
+    fn contents(&self) -> String {
         let reminders_str = self
             .reminders
             .iter()
             .map(|rem| rem.serialize())
             .collect::<String>();
-        std::fs::write(file, reminders_str).unwrap_or_default();
+        format!("{}{}\n{reminders_str}", Self::HEADER, Self::VERSION)
+    }
+
+    // writes to temporary file and renames it over the old one,
+    // so readers see either old or new file, never half written one
+    fn save_file<P: AsRef<Path>>(file: P, contents: &str) -> Result<(), Error> {
+        let file = file.as_ref();
+        let tmp_file = file.with_extension("tmp");
+        std::fs::write(&tmp_file, contents).map_err(|e| Error::storage(&tmp_file, e))?;
+        std::fs::rename(&tmp_file, file).map_err(|e| Error::storage(file, e))
     }
 
-    fn load_file(&self, file: &str) -> Vec<Reminder> {
+    // lines that fail to parse are skipped and returned as errors
+    fn load_file(&self, file: &str) -> LoadedFile {
         let reminder_str = std::fs::read_to_string(file).unwrap_or_default();
-        let reminder_str = reminder_str.trim();
-        let reminder_lines = reminder_str.lines();
-        reminder_lines.map(Reminder::deserialize).collect()
+        let mut lines = reminder_str.lines().enumerate().peekable();
+        let version = match lines.peek().and_then(|(_, l)| l.strip_prefix(Self::HEADER)) {
+            Some(version) => {
+                lines.next();
+                version.trim().parse().unwrap_or(Self::VERSION)
+            }
+            // files written before the header existed
+            None => 1,
+        };
+        if version > Self::VERSION {
+            eprintln!("{file}: written by newer remind (v{version}), some reminders may not load");
+        }
+        let mut loaded = LoadedFile {
+            reminders: Vec::new(),
+            version,
+            errors: Vec::new(),
+        };
+        for (i, line) in lines {
+            let line = line.trim_end_matches('\r');
+            let reminder = if version == 1 {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                Reminder::deserialize_v1(line)
+            } else {
+                if line.trim().is_empty() || line.starts_with('#') {
+                    continue;
+                }
+                Reminder::deserialize(line)
+            };
+            match reminder {
+                Ok(reminder) => loaded.reminders.push(reminder),
+                Err(reason) => loaded.errors.push(ParseError {
+                    line: i + 1,
+                    text: line.to_owned(),
+                    reason,
+                }),
+            }
+        }
+        loaded
+    }
+
+    pub fn save(&mut self) -> Result<(), Error> {
+        Self::save_file(&self.file, &self.contents())?;
+        // journal only what actually got saved
+        if let Some((op, before)) = self.pending_history.take() {
+            let changes = journal::diff(&before, &self.reminders);
+            if !changes.is_empty() {
+                self.journal.append(self.actor, op, None, changes);
+            }
+        }
+        Ok(())
     }
 
-    pub fn save(&self) {
-        self.save_file(&self.file);
+    // remembers current reminders, once saved the difference goes to journal
+    // so commands that end up changing nothing leave no history behind
+    pub fn save_history(&mut self, op: Op) {
+        self.pending_history = Some((op, self.reminders.clone()));
     }
 
-    pub fn save_history(&self) {
-        let history_files = std::fs::read_dir(&self.history_dir)
-            .unwrap()
-            .map(|res| res.unwrap().path())
-            .collect::<Vec<_>>();
-        if history_files.len() >= Self::MAX_HISTORY {
-            std::fs::remove_file(history_files.into_iter().min().unwrap()).unwrap();
+    // applies changes on top of current reminders, matching by id,
+    // reminders changed since keep what changed (see journal::merge)
+    fn apply(&mut self, changes: &[Change]) {
+        for change in changes {
+            let id = change
+                .before
+                .as_ref()
+                .or(change.after.as_ref())
+                .map(|rem| &rem.id);
+            let pos = self.reminders.iter().position(|rem| Some(&rem.id) == id);
+            match (pos, &change.before, &change.after) {
+                (Some(i), Some(before), Some(after)) => {
+                    self.reminders[i] = journal::merge(before, &self.reminders[i], after)
+                }
+                (Some(i), None, Some(after)) => self.reminders[i] = after.clone(),
+                (Some(i), _, None) => {
+                    self.reminders.remove(i);
+                }
+                (None, None, Some(after)) => self.reminders.push(after.clone()),
+                (None, Some(before), Some(_)) => {
+                    println!("\"{}\" was removed since, left as is", before.title)
+                }
+                (None, _, None) => {}
+            }
         }
-        let now = Local::now();
-        self.save_file(&format!(
-            "{}\\reminders {}.txt",
-            self.history_dir,
-            now.format("%y-%m-%d %H-%M-%S.%3f")
-        ));
     }
 
-    pub fn undo(&mut self) {
-        let history_files = std::fs::read_dir(&self.history_dir)
-            .unwrap()
-            .map(|res| res.unwrap().path())
+    // reverts latest change made from cli, or latest daemon fire which re-arms its reminders
+    // fires are never reverted as a side effect, reminders keep what daemon did to them
+    pub fn undo(&mut self, fire: bool) -> Result<(), Error> {
+        let entries = self.journal.load();
+        let (done, _) = journal::undo_redo_stacks(&entries);
+        let done = done
+            .iter()
+            .filter_map(|seq| entries.iter().find(|entry| entry.seq == *seq))
+            .collect::<Vec<_>>();
+        let Some(pos) = done
+            .iter()
+            .rposition(|entry| (entry.op == Op::Fire) == fire)
+        else {
+            if fire {
+                println!("no fired reminders to re-arm");
+            } else {
+                println!("nothing to undo");
+            }
+            return Ok(());
+        };
+        let entry = done[pos];
+        let before = self.reminders.clone();
+        let changes = entry
+            .changes
+            .iter()
+            .rev()
+            .map(Change::inverse)
             .collect::<Vec<_>>();
-        if let Some(last_file) = history_files.into_iter().max() {
-            self.reminders = self.load_file(last_file.to_str().unwrap());
-            self.save();
-            std::fs::remove_file(&last_file).unwrap();
+        self.apply(&changes);
+        self.pending_history = None;
+        self.save()?;
+        let applied = journal::diff(&before, &self.reminders);
+        self.journal
+            .append(self.actor, Op::Undo, Some(entry.seq), applied);
+        if fire {
+            println!("re-armed: {entry}");
+            return Ok(());
+        }
+        println!("undone: {entry}");
+        let touches = |later: &Entry| {
+            later.changes.iter().any(|later| {
+                entry.changes.iter().any(|change| {
+                    let id = |change: &Change| {
+                        change
+                            .before
+                            .as_ref()
+                            .or(change.after.as_ref())
+                            .map(|rem| rem.id.clone())
+                    };
+                    id(change) == id(later)
+                })
+            })
+        };
+        for later in done[pos + 1..].iter().filter(|later| touches(later)) {
+            println!("kept daemon {later}, to re-arm it: remind undo fire");
+        }
+        Ok(())
+    }
+
+    pub fn redo(&mut self) -> Result<(), Error> {
+        let entries = self.journal.load();
+        let (_, undone) = journal::undo_redo_stacks(&entries);
+        let Some(entry) = undone
+            .last()
+            .and_then(|seq| entries.iter().find(|entry| entry.seq == *seq))
+        else {
+            println!("nothing to redo");
+            return Ok(());
+        };
+        let before = self.reminders.clone();
+        self.apply(&entry.changes);
+        self.pending_history = None;
+        self.save()?;
+        let applied = journal::diff(&before, &self.reminders);
+        self.journal
+            .append(self.actor, Op::Redo, Some(entry.seq), applied);
+        println!("redone: {entry}");
+        Ok(())
+    }
+
+    // every recorded change, oldest first
+    pub fn history(&self) {
+        let entries = self.journal.load();
+        if entries.is_empty() {
+            println!("no history");
+            return;
+        }
+        let (_, undone) = journal::undo_redo_stacks(&entries);
+        for entry in &entries {
+            let undone = if undone.contains(&entry.seq) {
+                " (undone)"
+            } else {
+                ""
+            };
+            println!(
+                "#{} {} {:<6} {}{undone}",
+                entry.seq,
+                entry.time.format("%y-%m-%d %H:%M:%S"),
+                entry.actor.as_str(),
+                entry.summary()
+            );
+        }
+    }
+
+    // reminders as they are on disk, never migrated, quarantined or saved
+    // ids are only given in memory, for explain which must not write
+    pub fn load_read_only(&mut self) {
+        self.reminders = self.load_file(&self.file).reminders;
+        self.assign_ids();
+    }
+
+    pub fn load(&mut self) -> Result<(), Error> {
+        let loaded = self.load_file(&self.file);
+        self.reminders = loaded.reminders;
+        if !loaded.errors.is_empty() {
+            self.quarantine(&loaded.errors)?;
+        }
+        let has_data = std::fs::metadata(&self.file).is_ok_and(|m| m.len() > 0);
+        let ids_changed = self.assign_ids();
+        if loaded.version < Self::VERSION && has_data {
+            self.migrate(loaded.version)?;
+        } else if !loaded.errors.is_empty() || ids_changed {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    // moves unreadable lines out of reminders file so they don't break it again
+    fn quarantine(&self, errors: &[ParseError]) -> Result<(), Error> {
+        let now = Local::now().format("%y-%m-%d %H:%M:%S");
+        let mut rejected = String::new();
+        for error in errors {
+            eprintln!("{}: {error}", self.file);
+            rejected += &format!("# {now} {error}\n{}\n", error.text);
+        }
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.rejected_file)
+            .and_then(|mut file| file.write_all(rejected.as_bytes()))
+            .map_err(|e| Error::storage(&self.rejected_file, e))?;
+        eprintln!(
+            "moved {} unreadable reminder(s) to: {}, run \"remind doctor\"",
+            errors.len(),
+            self.rejected_file
+        );
+        Ok(())
+    }
+
+    // (reason, line) pairs from rejected sidecar file
+    fn load_rejected(&self) -> Vec<(String, String)> {
+        let rejected = std::fs::read_to_string(&self.rejected_file).unwrap_or_default();
+        let mut entries = Vec::new();
+        let mut reason = String::new();
+        for line in rejected.lines() {
+            match line.strip_prefix("# ") {
+                Some(comment) => reason = comment.to_owned(),
+                None if !line.is_empty() => {
+                    entries.push((std::mem::take(&mut reason), line.to_owned()))
+                }
+                None => {}
+            }
+        }
+        entries
+    }
+
+    fn repeats_without_interval(reminder: &Reminder) -> bool {
+        reminder.repeats == 0
+            && reminder.interval.is_zero()
+            && reminder.rrule.is_none()
+            && reminder.cron.is_none()
+    }
+
+    // rejected lines and suspicious reminders
+    pub fn diagnose(&self) -> Diagnosis {
+        let mut problems = Vec::new();
+        for (i, reminder) in self.reminders.iter().enumerate() {
+            if Self::repeats_without_interval(reminder) {
+                problems.push((i, format!("{reminder} repeats forever without interval")));
+            }
+        }
+        Diagnosis {
+            rejected: self.load_rejected(),
+            problems,
+        }
+    }
+
+    // reports rejected lines and suspicious reminders, repairs them if asked to
+    pub fn doctor(&mut self, fix: bool) -> Result<(), Error> {
+        let Diagnosis { rejected, problems } = self.diagnose();
+        println!("reminders: {} in {}", self.reminders.len(), self.file);
+        if rejected.is_empty() && problems.is_empty() {
+            println!("no problems found");
+            return Ok(());
+        }
+        if !rejected.is_empty() {
+            println!("rejected: {} in {}", rejected.len(), self.rejected_file);
+            for (reason, line) in &rejected {
+                println!("  {reason}\n    {line}");
+            }
+        }
+        if !problems.is_empty() {
+            println!("problems:");
+            for (_, problem) in &problems {
+                println!("  {problem}");
+            }
+        }
+        if !fix {
+            println!("run \"remind doctor fix\" to repair");
+            return Ok(());
+        }
+
+        for (i, _) in problems {
+            self.reminders[i].repeats = 1;
+        }
+        let mut unrepairable = String::new();
+        for (reason, line) in rejected {
+            match Reminder::repair(&line) {
+                Some(mut reminder) => {
+                    if Self::repeats_without_interval(&reminder) {
+                        reminder.repeats = 1;
+                    }
+                    reminder.id = self.new_id();
+                    println!("repaired: {reminder}");
+                    self.reminders.push(reminder);
+                }
+                None => unrepairable += &format!("# {reason}\n{line}\n"),
+            }
+        }
+        self.save()?;
+        if unrepairable.is_empty() {
+            std::fs::remove_file(&self.rejected_file).unwrap_or_default();
+        } else {
+            std::fs::write(&self.rejected_file, &unrepairable)
+                .map_err(|e| Error::storage(&self.rejected_file, e))?;
+            println!(
+                "could not repair some lines, they are kept in: {}",
+                self.rejected_file
+            );
+        }
+        Ok(())
+    }
+
+    // rewrites old format file in current format, keeping a copy of the original
+    fn migrate(&mut self, version: u32) -> Result<(), Error> {
+        let backup = Path::new(&self.file).with_extension(format!("v{version}"));
+        std::fs::copy(&self.file, &backup).map_err(|e| Error::storage(&backup, e))?;
+        self.save()?;
+        println!(
+            "upgraded reminders file to v{}, original saved at: {}",
+            Self::VERSION,
+            backup.display()
+        );
+        Ok(())
+    }
+
+    fn new_id(&self) -> String {
+        generate_id(|id| self.reminders.iter().any(|rem| rem.id == id))
+    }
+
+    // gives reminders from older files (or duplicated by hand editing) their own id
+    // returns true if any id changed
+    fn assign_ids(&mut self) -> bool {
+        let mut changed = false;
+        for i in 0..self.reminders.len() {
+            let id = &self.reminders[i].id;
+            if id.is_empty() || self.reminders[..i].iter().any(|rem| rem.id == *id) {
+                self.reminders[i].id = self.new_id();
+                changed = true;
+            }
         }
+        changed
+    }
+
+    // reminder with exactly matching id, otherwise best fuzzy title match
+    fn find(&self, query: &str) -> Option<usize> {
+        let by_id = self
+            .reminders
+            .iter()
+            .position(|rem| rem.id.eq_ignore_ascii_case(query.trim()));
+        by_id.or_else(|| self.match_title(query))
     }
 
-    pub fn load(&mut self) {
-        self.reminders = self.load_file(&self.file);
+    // reminder with matching id or title, see find
+    pub fn get(&self, query: &str) -> Option<&Reminder> {
+        self.find(query).map(|i| &self.reminders[i])
     }
 
     fn match_title(&self, title: &str) -> Option<usize> {
@@ -130,112 +590,222 @@ impl ReminderFile {
             .map(|(i, _)| i)
     }
 
-    pub fn remove_line(&mut self, line: usize) {
+    pub fn remove_line(&mut self, line: usize) -> Result<(), Error> {
         self.reminders.remove(line);
-        self.save();
+        self.save()
     }
 
-    pub fn remove(&mut self, title: &str) {
-        if let Some(best_match_idx) = self.match_title(title) {
+    pub fn remove(&mut self, query: &str) -> Result<(), Error> {
+        if let Some(best_match_idx) = self.find(query) {
             println!("removed: {}", &self.reminders[best_match_idx]);
-            self.remove_line(best_match_idx);
-        } else {
-            println!("no reminders with title \"{title}\" found");
+            return self.remove_line(best_match_idx);
+        }
+        println!("no reminders with id or title \"{query}\" found");
+        Ok(())
+    }
+
+    // applies changes to best matching reminder and shows what changed
+    // patch works on a copy, a rejected edit leaves reminder as it was
+    pub fn edit(
+        &mut self,
+        query: &str,
+        patch: impl FnOnce(&mut Reminder) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        let Some(i) = self.find(query) else {
+            println!("no reminders with id or title \"{query}\" found");
+            return Ok(());
+        };
+        let mut reminder = self.reminders[i].clone();
+        patch(&mut reminder)?;
+        let before = self.reminders[i].to_string();
+        let after = reminder.to_string();
+        if before == after {
+            println!("unchanged: {after}");
+            return Ok(());
         }
+        self.reminders[i] = reminder;
+        self.save()?;
+        println!("edited:\n- {before}\n+ {after}");
+        Ok(())
     }
 
-    pub fn remove_last(&mut self) {
+    pub fn remove_last(&mut self) -> Result<(), Error> {
         if let Some(last) = self.reminders.last() {
             println!("removed: {}", last);
-            self.remove_line(self.reminders.len() - 1);
-        } else {
-            println!("no last reminder");
+            self.save_history(Op::Remove);
+            return self.remove_line(self.reminders.len() - 1);
         }
+        println!("no last reminder");
+        Ok(())
     }
 
-    pub fn skip(&mut self, title: &str, skips: u32) {
-        if let Some(best_match_idx) = self.match_title(title) {
+    pub fn skip(&mut self, query: &str, skips: u32) -> Result<(), Error> {
+        if let Some(best_match_idx) = self.find(query) {
             self.reminders[best_match_idx].skips += skips;
-            self.save();
-        } else {
-            println!("no reminders with title \"{title}\" found");
+            return self.save();
         }
+        println!("no reminders with id or title \"{query}\" found");
+        Ok(())
     }
 
-    pub fn skip_next(&mut self, skips: u32) {
+    pub fn skip_next(&mut self, skips: u32) -> Result<(), Error> {
         if let Some(i) = self.closest_reminder() {
             self.reminders[i].skips += skips;
-            self.save();
-        } else {
-            println!("no next reminder");
+            return self.save();
         }
+        println!("no next reminder");
+        Ok(())
     }
 
-    pub fn wait_next(&mut self) {
-        if !Path::new(&self.file).exists() {
-            std::fs::File::create_new(&self.file).unwrap();
-        }
-        let (tx, rx) = std::sync::mpsc::channel();
-        let mut watcher = notify::recommended_watcher(tx).unwrap();
-        watcher
-            .watch(Path::new(&self.file), RecursiveMode::NonRecursive)
-            .unwrap();
-        self.load();
-        println!("reminders loaded from file: {}", self.reminders.len());
-        loop {
-            if let Ok(res) = rx.try_recv() {
-                match res {
-                    Ok(e) => match e.kind {
-                        EventKind::Create(create_kind) => {
-                            if create_kind == notify::event::CreateKind::File {
-                                self.load();
-                            }
-                        }
-
-                        EventKind::Modify(modify_kind) => match modify_kind {
-                            notify::event::ModifyKind::Any | notify::event::ModifyKind::Data(_) => {
-                                self.load();
-                            }
-                            notify::event::ModifyKind::Name(_) => {
-                                self.reminders.clear();
-                            }
-                            _ => {}
-                        },
-                        EventKind::Remove(remove_kind) => {
-                            if remove_kind == notify::event::RemoveKind::File {
-                                self.reminders.clear();
-                            }
-                        }
-                        _ => {}
-                    },
-                    Err(e) => eprintln!("{e}"),
-                }
+    pub fn path(&self) -> &Path {
+        Path::new(&self.file)
+    }
+
+    // true if event is a change to reminders file
+    pub fn is_file_event(&self, event: &notify::Event) -> bool {
+        !matches!(event.kind, EventKind::Access(_))
+            && event.paths.iter().any(|path| path.ends_with(&self.file))
+    }
+
+    // time left until closest reminder is due, None if there are no reminders
+    pub fn time_until_next(&self) -> Option<std::time::Duration> {
+        let i = self.closest_reminder()?;
+        let until = self.reminders[i].end_time - Local::now();
+        Some(until.to_std().unwrap_or_default())
+    }
+
+    fn notify(summary: &str, body: Option<&str>, timeout: Option<u32>) {
+        let mut notification = notify_rust::Notification::new();
+        notification.summary(summary);
+        match timeout {
+            Some(0) => notification.timeout(notify_rust::Timeout::Never),
+            // huge timeouts from config stay up as long as milliseconds fit
+            Some(secs) => notification.timeout(notify_rust::Timeout::Milliseconds(
+                secs.saturating_mul(1000),
+            )),
+            None => notification.timeout(notify_rust::Timeout::Default),
+        };
+        if let Some(body) = body {
+            notification.body(body);
+        }
+        if let Err(e) = notification.show() {
+            eprintln!("failed to show \"{summary}\" notification: {e}");
+        }
+    }
+
+    // advances every reminder that is due and drops finished ones
+    // returns true if anything changed and needs saving, and the notifications to show
+    pub fn fire_due(&mut self) -> (bool, Vec<(String, Option<String>)>) {
+        let now = Local::now();
+        let grace = Duration::minutes(1);
+        let default_catch_up = self.catch_up;
+        let mut missed = Vec::new();
+        let mut changed = false;
+        let mut notifications = Vec::new();
+        let label = match &self.label {
+            Some(label) => format!("[{label}] "),
+            None => String::new(),
+        };
+        self.reminders.retain_mut(|reminder| {
+            if reminder.end_time > now {
+                return true;
             }
-            if let Some(i) = self.closest_reminder() {
-                let closest_reminder = &mut self.reminders[i];
-                let (updated, should_remove) = closest_reminder.update();
-                if updated {
-                    if closest_reminder.skips > 0 {
-                        closest_reminder.skips -= 1;
-                    } else {
-                        notify_rust::Notification::new()
-                            .summary(&closest_reminder.title)
-                            .show()
-                            .unwrap();
-                    }
-                    watcher.unwatch(Path::new(&self.file)).unwrap();
-                    if should_remove {
-                        self.remove_line(i);
-                    } else {
-                        self.save();
+            let first_due = reminder.end_time;
+            let (due, should_remove) = reminder.update();
+            if due == 0 {
+                return !should_remove;
+            }
+            changed = true;
+            let title = format!("{label}{}", reminder.title);
+            let skipped = due.min(reminder.skips);
+            reminder.skips -= skipped;
+            let due = due - skipped;
+            // occurrence was due well before now, daemon was asleep or not running
+            let late = now - first_due > grace;
+            if late && due > 0 {
+                missed.push(format!(
+                    "{label}\"{}\" missed {due} time{} since {}, caught up at {}",
+                    reminder.title,
+                    if due == 1 { "" } else { "s" },
+                    first_due.format("%y-%m-%d %H:%M:%S"),
+                    now.format("%y-%m-%d %H:%M:%S"),
+                ));
+            }
+            match reminder.catch_up.unwrap_or(default_catch_up) {
+                _ if due == 0 => {}
+                CatchUp::Drop if late => {}
+                CatchUp::Each => {
+                    for _ in 0..due {
+                        notifications.push((title.clone(), None));
                     }
-                    watcher
-                        .watch(Path::new(&self.file), RecursiveMode::NonRecursive)
-                        .unwrap();
                 }
+                CatchUp::Once if due > 1 => {
+                    notifications.push((title, Some(format!("missed {due} times"))))
+                }
+                CatchUp::Once | CatchUp::Drop => notifications.push((title, None)),
+            }
+            !should_remove
+        });
+        if !missed.is_empty() {
+            let lines = missed
+                .iter()
+                .map(|line| format!("{line}\n"))
+                .collect::<String>();
+            let written = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.missed_file)
+                .and_then(|mut file| file.write_all(lines.as_bytes()));
+            if let Err(e) = written {
+                eprintln!("{}", Error::storage(&self.missed_file, e));
             }
-            std::thread::sleep(std::time::Duration::from_millis(500));
         }
+        (changed, notifications)
+    }
+
+    // prints reminders that came due while daemon was away, then forgets them
+    pub fn missed(&self) {
+        let missed = std::fs::read_to_string(&self.missed_file).unwrap_or_default();
+        if missed.trim().is_empty() {
+            println!("no missed reminders");
+            return;
+        }
+        print!("{missed}");
+        std::fs::remove_file(&self.missed_file).unwrap_or_default();
+    }
+
+    // loads reminders for daemon, changes from here on are recorded as made by daemon
+    pub fn start_daemon(&mut self) -> Result<(), Error> {
+        self.actor = Actor::Daemon;
+        if !Path::new(&self.file).exists() {
+            std::fs::File::create_new(&self.file).map_err(|e| Error::storage(&self.file, e))?;
+        }
+        let _lock = self.lock()?;
+        self.load()?;
+        println!(
+            "{} reminders loaded from: {}",
+            self.reminders.len(),
+            self.file
+        );
+        Ok(())
+    }
+
+    // reloads under lock so reminders added by cli meanwhile are not overwritten,
+    // then fires whatever is due
+    pub fn refresh(&mut self) -> Result<(), Error> {
+        let _lock = self.lock()?;
+        self.load()?;
+        self.save_history(Op::Fire);
+        let (changed, notifications) = self.fire_due();
+        let saved = match changed {
+            true => self.save(),
+            false => Ok(()),
+        };
+        self.pending_history = None;
+        for (summary, body) in notifications {
+            Self::notify(&summary, body.as_deref(), self.notification_timeout);
+        }
+        saved
     }
 
     pub fn list(&mut self) {
@@ -244,13 +814,35 @@ impl ReminderFile {
         }
     }
 
-    pub fn clear(&mut self) {
-        if self.reminders.is_empty() {
+    // list with next count fire times under each reminder
+    pub fn list_expanded(&self, count: usize) {
+        for reminder in self.reminders.iter() {
+            println!("{reminder}");
+            print_occurrences(reminder, count);
+        }
+    }
+
+    pub fn agenda(&self, span: Span) {
+        agenda::print(&self.reminders, span);
+    }
+
+    // next count fire times of reminder with matching id or title
+    pub fn next(&self, query: &str, count: usize) {
+        let Some(reminder) = self.get(query) else {
+            println!("no reminders with id or title \"{query}\" found");
             return;
+        };
+        println!("{reminder}");
+        print_occurrences(reminder, count);
+    }
+
+    pub fn clear(&mut self) -> Result<(), Error> {
+        if self.reminders.is_empty() {
+            return Ok(());
         }
         println!("cleared:");
         self.list();
         self.reminders.clear();
-        self.save();
+        self.save()
     }
 }
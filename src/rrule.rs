@@ -0,0 +1,473 @@
+use chrono::{
+    Datelike, Duration, Local, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike,
+    Utc, Weekday,
+};
+
+// RFC 5545 recurrence rule, supports:
+// FREQ, INTERVAL, BYDAY (with ordinals), BYMONTHDAY, BYMONTH, BYSETPOS, COUNT, UNTIL
+// weeks always start on monday, time of day comes from the start date
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RRule {
+    pub freq: Freq,
+    pub interval: u32,
+    // (ordinal, weekday), ordinal 0 means every such weekday
+    pub by_day: Vec<(i32, Weekday)>,
+    pub by_month_day: Vec<i32>,
+    pub by_month: Vec<u32>,
+    pub by_set_pos: Vec<i32>,
+    pub count: Option<u32>,
+    // local time
+    pub until: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Freq {
+    Yearly,
+    Monthly,
+    Weekly,
+    Daily,
+    Hourly,
+    Minutely,
+    Secondly,
+}
+
+impl Freq {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Yearly => "YEARLY",
+            Self::Monthly => "MONTHLY",
+            Self::Weekly => "WEEKLY",
+            Self::Daily => "DAILY",
+            Self::Hourly => "HOURLY",
+            Self::Minutely => "MINUTELY",
+            Self::Secondly => "SECONDLY",
+        }
+    }
+
+    fn is_sub_daily(&self) -> bool {
+        matches!(self, Self::Hourly | Self::Minutely | Self::Secondly)
+    }
+}
+
+const WEEKDAY_CODES: [(&str, Weekday); 7] = [
+    ("MO", Weekday::Mon),
+    ("TU", Weekday::Tue),
+    ("WE", Weekday::Wed),
+    ("TH", Weekday::Thu),
+    ("FR", Weekday::Fri),
+    ("SA", Weekday::Sat),
+    ("SU", Weekday::Sun),
+];
+
+// gives up if this many periods in a row produce no occurrence (e.g. FREQ=YEARLY;BYMONTH=2;BYMONTHDAY=30)
+const MAX_EMPTY_PERIODS: u32 = 10_000;
+
+fn days_in_month(date: NaiveDate) -> u32 {
+    let first = date.with_day(1).unwrap();
+    let next = first.checked_add_months(Months::new(1)).unwrap();
+    (next - first).num_days() as u32
+}
+
+fn days_in_year(date: NaiveDate) -> u32 {
+    let first = NaiveDate::from_ymd_opt(date.year(), 1, 1).unwrap();
+    let next = NaiveDate::from_ymd_opt(date.year() + 1, 1, 1).unwrap();
+    (next - first).num_days() as u32
+}
+
+fn parse_list<T>(
+    value: &str,
+    key: &str,
+    parse: impl Fn(&str) -> Option<T>,
+) -> Result<Vec<T>, String> {
+    value
+        .split(',')
+        .map(|item| parse(item.trim()).ok_or(format!("invalid {key} value \"{item}\"")))
+        .collect()
+}
+
+fn parse_until(value: &str) -> Option<NaiveDateTime> {
+    if let Some(utc) = value.strip_suffix('Z') {
+        let utc = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some(
+            Utc.from_utc_datetime(&utc)
+                .with_timezone(&Local)
+                .naive_local(),
+        );
+    }
+    NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y%m%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(23, 59, 59))
+        })
+}
+
+impl RRule {
+    // next occurrence strictly after `after`, which must itself be an occurrence
+    // (or the rule's start) so INTERVAL stays aligned, COUNT is not applied here
+    pub fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut occurrences = self.iter(after);
+        occurrences.count = None;
+        occurrences.find(|dt| *dt > after)
+    }
+
+    // occurrences at or after `start`, honoring COUNT and UNTIL
+    pub fn iter(&self, start: NaiveDateTime) -> Occurrences<'_> {
+        Occurrences {
+            rule: self,
+            start,
+            period: 0,
+            buffer: Vec::new(),
+            emitted: 0,
+            count: self.count,
+            done: false,
+        }
+    }
+
+    // start of the n-th period counted from the one containing `start`
+    fn period_start(&self, start: NaiveDateTime, n: u32) -> Option<NaiveDateTime> {
+        let steps = n.checked_mul(self.interval)?;
+        let date = start.date();
+        let midnight = NaiveTime::MIN;
+        // huge INTERVAL runs past the last representable date, no more periods then
+        Some(match self.freq {
+            Freq::Yearly => {
+                let year = date.year().checked_add(steps.try_into().ok()?)?;
+                NaiveDate::from_ymd_opt(year, 1, 1)?.and_time(midnight)
+            }
+            Freq::Monthly => date
+                .with_day(1)?
+                .checked_add_months(Months::new(steps))?
+                .and_time(midnight),
+            Freq::Weekly => {
+                let monday = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+                monday
+                    .checked_add_signed(Duration::weeks(steps as i64))?
+                    .and_time(midnight)
+            }
+            Freq::Daily => date
+                .checked_add_signed(Duration::days(steps as i64))?
+                .and_time(midnight),
+            Freq::Hourly => start
+                .with_minute(0)?
+                .with_second(0)?
+                .with_nanosecond(0)?
+                .checked_add_signed(Duration::hours(steps as i64))?,
+            Freq::Minutely => start
+                .with_second(0)?
+                .with_nanosecond(0)?
+                .checked_add_signed(Duration::minutes(steps as i64))?,
+            Freq::Secondly => start
+                .with_nanosecond(0)?
+                .checked_add_signed(Duration::seconds(steps as i64))?,
+        })
+    }
+
+    fn month_day_matches(&self, date: NaiveDate) -> bool {
+        let day = date.day() as i32;
+        let last = days_in_month(date) as i32;
+        self.by_month_day.is_empty()
+            || self
+                .by_month_day
+                .iter()
+                .any(|&md| md == day || (md < 0 && last + md + 1 == day))
+    }
+
+    fn weekday_matches(&self, date: NaiveDate) -> bool {
+        // ordinals count within the month or year, only for MONTHLY and YEARLY
+        let (index, len) = match self.freq {
+            Freq::Monthly => (date.day() - 1, days_in_month(date)),
+            Freq::Yearly if self.by_month.is_empty() => (date.ordinal0(), days_in_year(date)),
+            Freq::Yearly => (date.day() - 1, days_in_month(date)),
+            _ => (0, 0),
+        };
+        let ordinal_matches = |ordinal: i32| {
+            len == 0
+                || ordinal == 0
+                || ordinal == (index / 7 + 1) as i32
+                || ordinal == -(((len - index - 1) / 7 + 1) as i32)
+        };
+        self.by_day.is_empty()
+            || self
+                .by_day
+                .iter()
+                .any(|&(ordinal, weekday)| date.weekday() == weekday && ordinal_matches(ordinal))
+    }
+
+    fn date_matches(&self, date: NaiveDate, start: NaiveDate) -> bool {
+        if !self.by_month.is_empty() && !self.by_month.contains(&date.month()) {
+            return false;
+        }
+        let implicit = self.by_month_day.is_empty() && self.by_day.is_empty();
+        match self.freq {
+            Freq::Yearly if implicit => {
+                date.day() == start.day()
+                    && (!self.by_month.is_empty() || date.month() == start.month())
+            }
+            Freq::Monthly if implicit => date.day() == start.day(),
+            Freq::Weekly if self.by_day.is_empty() => {
+                date.weekday() == start.weekday() && self.month_day_matches(date)
+            }
+            _ => self.month_day_matches(date) && self.weekday_matches(date),
+        }
+    }
+
+    // all occurrences within the period, sorted, BYSETPOS applied
+    fn candidates(&self, period: NaiveDateTime, start: NaiveDateTime) -> Vec<NaiveDateTime> {
+        let first = period.date();
+        let days = match self.freq {
+            Freq::Yearly => days_in_year(first),
+            Freq::Monthly => days_in_month(first),
+            Freq::Weekly => 7,
+            _ => 1,
+        };
+        let mut set: Vec<NaiveDateTime> = (0..days)
+            .map(|i| first + Duration::days(i as i64))
+            .filter(|date| self.date_matches(*date, start.date()))
+            .map(|date| match self.freq {
+                Freq::Hourly => {
+                    period + Duration::seconds((start.minute() * 60 + start.second()) as i64)
+                }
+                Freq::Minutely => period + Duration::seconds(start.second() as i64),
+                Freq::Secondly => period,
+                _ => date.and_time(start.time().with_nanosecond(0).unwrap_or_default()),
+            })
+            .collect();
+        if !self.by_set_pos.is_empty() {
+            let len = set.len() as i32;
+            let mut picked: Vec<NaiveDateTime> = self
+                .by_set_pos
+                .iter()
+                .filter_map(|&pos| {
+                    let i = if pos > 0 { pos - 1 } else { len + pos };
+                    (0..len).contains(&i).then(|| set[i as usize])
+                })
+                .collect();
+            picked.sort();
+            picked.dedup();
+            set = picked;
+        }
+        set
+    }
+
+    // sub-daily periods on a non matching date can skip straight to next midnight
+    fn periods_to_next_day(&self, period: NaiveDateTime) -> u32 {
+        let unit = match self.freq {
+            Freq::Hourly => 3600,
+            Freq::Minutely => 60,
+            _ => 1,
+        };
+        let step = unit * self.interval as i64;
+        let next_day = (period.date() + Duration::days(1)).and_time(NaiveTime::MIN);
+        let secs = (next_day - period).num_seconds();
+        ((secs + step - 1) / step).max(1) as u32
+    }
+}
+
+impl std::str::FromStr for RRule {
+    type Err = String;
+
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        let str = str.trim();
+        let str = if str.len() >= 6 && str[..6].eq_ignore_ascii_case("rrule:") {
+            &str[6..]
+        } else {
+            str
+        };
+        let mut freq = None;
+        let mut rule = RRule {
+            freq: Freq::Daily,
+            interval: 1,
+            by_day: Vec::new(),
+            by_month_day: Vec::new(),
+            by_month: Vec::new(),
+            by_set_pos: Vec::new(),
+            count: None,
+            until: None,
+        };
+        for part in str.split(';').filter(|part| !part.trim().is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or(format!("invalid rrule part \"{part}\", expected KEY=VALUE"))?;
+            let (key, value) = (key.trim().to_uppercase(), value.trim().to_uppercase());
+            match key.as_str() {
+                "FREQ" => {
+                    freq = Some(match value.as_str() {
+                        "YEARLY" => Freq::Yearly,
+                        "MONTHLY" => Freq::Monthly,
+                        "WEEKLY" => Freq::Weekly,
+                        "DAILY" => Freq::Daily,
+                        "HOURLY" => Freq::Hourly,
+                        "MINUTELY" => Freq::Minutely,
+                        "SECONDLY" => Freq::Secondly,
+                        _ => return Err(format!("invalid FREQ \"{value}\"")),
+                    })
+                }
+                "INTERVAL" => {
+                    rule.interval = value
+                        .parse()
+                        .ok()
+                        .filter(|interval| *interval > 0)
+                        .ok_or(format!("invalid INTERVAL \"{value}\""))?
+                }
+                "COUNT" => {
+                    rule.count = Some(
+                        value
+                            .parse()
+                            .ok()
+                            .filter(|count| *count > 0)
+                            .ok_or(format!("invalid COUNT \"{value}\""))?,
+                    )
+                }
+                "UNTIL" => {
+                    rule.until =
+                        Some(parse_until(&value).ok_or(format!("invalid UNTIL \"{value}\""))?)
+                }
+                "BYDAY" => {
+                    rule.by_day = parse_list(&value, "BYDAY", |day| {
+                        let split = day.len().checked_sub(2)?;
+                        let (ordinal, code) = day.split_at_checked(split)?;
+                        let weekday = WEEKDAY_CODES.iter().find(|(c, _)| *c == code)?.1;
+                        let ordinal = match ordinal {
+                            "" => 0,
+                            ordinal => ordinal.trim_start_matches('+').parse().ok()?,
+                        };
+                        (ordinal == 0 || (1..=53).contains(&i32::abs(ordinal)))
+                            .then_some((ordinal, weekday))
+                    })?
+                }
+                "BYMONTHDAY" => {
+                    rule.by_month_day = parse_list(&value, "BYMONTHDAY", |day| {
+                        let day: i32 = day.parse().ok()?;
+                        (1..=31).contains(&day.abs()).then_some(day)
+                    })?
+                }
+                "BYMONTH" => {
+                    rule.by_month = parse_list(&value, "BYMONTH", |month| {
+                        let month: u32 = month.parse().ok()?;
+                        (1..=12).contains(&month).then_some(month)
+                    })?
+                }
+                "BYSETPOS" => {
+                    rule.by_set_pos = parse_list(&value, "BYSETPOS", |pos| {
+                        let pos: i32 = pos.parse().ok()?;
+                        (1..=366).contains(&pos.abs()).then_some(pos)
+                    })?
+                }
+                "WKST" if value == "MO" => {}
+                _ => return Err(format!("unsupported rrule part \"{part}\"")),
+            }
+        }
+        rule.freq = freq.ok_or("rrule is missing FREQ")?;
+        if rule.count.is_some() && rule.until.is_some() {
+            return Err("rrule can't have both COUNT and UNTIL".to_string());
+        }
+        // second period has to exist, even counted from today
+        let today = Local::now().naive_local();
+        if rule.period_start(today, 1).is_none() {
+            return Err(format!("INTERVAL={} is too large", rule.interval));
+        }
+        Ok(rule)
+    }
+}
+
+impl std::fmt::Display for RRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let join = |items: Vec<String>| items.join(",");
+        write!(f, "FREQ={}", self.freq.as_str())?;
+        if self.interval > 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if !self.by_day.is_empty() {
+            let days = self.by_day.iter().map(|&(ordinal, weekday)| {
+                let code = WEEKDAY_CODES
+                    .iter()
+                    .find(|(_, wd)| *wd == weekday)
+                    .unwrap()
+                    .0;
+                match ordinal {
+                    0 => code.to_string(),
+                    n => format!("{n}{code}"),
+                }
+            });
+            write!(f, ";BYDAY={}", join(days.collect()))?;
+        }
+        if !self.by_month_day.is_empty() {
+            let days = self.by_month_day.iter().map(|d| d.to_string());
+            write!(f, ";BYMONTHDAY={}", join(days.collect()))?;
+        }
+        if !self.by_month.is_empty() {
+            let months = self.by_month.iter().map(|m| m.to_string());
+            write!(f, ";BYMONTH={}", join(months.collect()))?;
+        }
+        if !self.by_set_pos.is_empty() {
+            let positions = self.by_set_pos.iter().map(|p| p.to_string());
+            write!(f, ";BYSETPOS={}", join(positions.collect()))?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={count}")?;
+        }
+        if let Some(until) = self.until {
+            write!(f, ";UNTIL={}", until.format("%Y%m%dT%H%M%S"))?;
+        }
+        Ok(())
+    }
+}
+
+pub struct Occurrences<'a> {
+    rule: &'a RRule,
+    start: NaiveDateTime,
+    period: u32,
+    // current period's occurrences, reversed
+    buffer: Vec<NaiveDateTime>,
+    emitted: u32,
+    count: Option<u32>,
+    done: bool,
+}
+
+impl Iterator for Occurrences<'_> {
+    type Item = NaiveDateTime;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            if let Some(dt) = self.buffer.pop() {
+                if dt < self.start {
+                    continue;
+                }
+                if self.rule.until.is_some_and(|until| dt > until)
+                    || self.count.is_some_and(|count| self.emitted >= count)
+                {
+                    self.done = true;
+                    return None;
+                }
+                self.emitted += 1;
+                return Some(dt);
+            }
+            let mut empty_periods = 0;
+            while self.buffer.is_empty() && !self.done {
+                let Some(period) = self.rule.period_start(self.start, self.period) else {
+                    self.done = true;
+                    break;
+                };
+                if empty_periods >= MAX_EMPTY_PERIODS
+                    || self.rule.until.is_some_and(|until| period > until)
+                {
+                    self.done = true;
+                    break;
+                }
+                empty_periods += 1;
+                if self.rule.freq.is_sub_daily()
+                    && !self.rule.date_matches(period.date(), self.start.date())
+                {
+                    self.period += self.rule.periods_to_next_day(period);
+                    continue;
+                }
+                self.buffer = self.rule.candidates(period, self.start);
+                self.buffer.reverse();
+                self.period += 1;
+            }
+        }
+        None
+    }
+}
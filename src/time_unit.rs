@@ -41,9 +41,9 @@ impl DerefMut for TimeUnit {
     }
 }
 
-impl std::fmt::Debug for TimeUnit {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let unit = match self {
+impl TimeUnit {
+    pub fn name(&self) -> &'static str {
+        match self {
             Self::Second(_) => "second",
             Self::Minute(_) => "minute",
             Self::Hour(_) => "hour",
@@ -51,8 +51,26 @@ impl std::fmt::Debug for TimeUnit {
             Self::Week(_) => "week",
             Self::Month(_) => "month",
             Self::Year(_) => "year",
-        };
+        }
+    }
+
+    // second is smallest
+    pub fn rank(&self) -> u8 {
+        match self {
+            Self::Second(_) => 0,
+            Self::Minute(_) => 1,
+            Self::Hour(_) => 2,
+            Self::Day(_) => 3,
+            Self::Week(_) => 4,
+            Self::Month(_) => 5,
+            Self::Year(_) => 6,
+        }
+    }
+}
+
+impl std::fmt::Debug for TimeUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let time = *self.deref();
-        f.write_fmt(format_args!("{time} {unit}"))
+        f.write_fmt(format_args!("{time} {}", self.name()))
     }
 }
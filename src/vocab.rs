@@ -0,0 +1,655 @@
+use chrono::NaiveTime;
+
+use crate::{TimeUnit, FRIDAY, MONDAY, SATURDAY, SUNDAY, THURSDAY, TUESDAY, WEDNESDAY};
+
+// meaning of a vocabulary word, numbers written next to it (3d, rep4) are added by tokenize
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Word {
+    Number(u32),
+    // time unit, value replaced by attached number
+    Unit(TimeUnit),
+    // repeating time unit: hourly, daily...
+    Every(TimeUnit),
+    Tomorrow,
+    // noon, evening: clock time it stands for, [times] in config can move it
+    DayPart(NaiveTime),
+    WeekDay(u8),
+    // 0 based
+    Month(u32),
+    // rep 4, rep4
+    Repeat,
+    // fixed repeat count, 0 for forever
+    RepeatTimes(u32),
+    // 4x, x4
+    Times,
+    // next friday, next week: one more period ahead
+    Next,
+    // in 2 hours, after 3 days: only marks a duration that follows
+    After,
+    // this friday, on friday: the coming one
+    This,
+    // between 9am and 5pm: time of day window
+    Between,
+    // a week, the day: before a unit
+    Article,
+    Skip,
+    CatchUp,
+    Cron,
+    Edit,
+    Remove,
+    Clear,
+    List,
+    Undo,
+    UnFire,
+    Redo,
+    History,
+    Missed,
+    Doctor,
+    Explain,
+    Agenda,
+    Help,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Group {
+    Time,
+    // matched only when written exactly, "even" in a title shouldn't set time to evening
+    DayPart,
+    WeekDay,
+    Month,
+    Repeat,
+    Number,
+    // matched only when written exactly, a typo in a title should never remove reminders
+    Command,
+}
+
+impl Group {
+    const ALL: [Group; 7] = [
+        Self::Time,
+        Self::DayPart,
+        Self::WeekDay,
+        Self::Month,
+        Self::Repeat,
+        Self::Number,
+        Self::Command,
+    ];
+
+    fn title(&self) -> &'static str {
+        match self {
+            Self::Time => "Time",
+            Self::DayPart => "Time of day",
+            Self::WeekDay => "Weekday",
+            Self::Month => "Month",
+            Self::Repeat => "Repeat",
+            Self::Number => "Numbers",
+            Self::Command => "Commands",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Entry {
+    word: Word,
+    group: Group,
+    // first one is shown first in help
+    names: Vec<String>,
+    note: &'static str,
+}
+
+// every word tokenize understands, also the source of aliases section in help
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Vocab {
+    entries: Vec<Entry>,
+}
+
+// shortest prefix that matches a longer word (wedn -> wednesday)
+const MIN_PREFIX: usize = 4;
+// shortest words where one typo is forgiven (mintue -> minute), and two typos
+const MIN_TYPO_1: usize = 6;
+const MIN_TYPO_2: usize = 9;
+
+// optimal string alignment distance, adjacent swaps count as one edit
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    d[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+impl Default for Vocab {
+    fn default() -> Self {
+        let mut vocab = Self {
+            entries: Vec::new(),
+        };
+        use Group as G;
+        use TimeUnit as T;
+        use Word as W;
+        let mut add = |group, word, names: &[&str], note| {
+            vocab.entries.push(Entry {
+                word,
+                group,
+                names: names.iter().map(|name| name.to_string()).collect(),
+                note,
+            })
+        };
+        add(
+            G::Time,
+            W::Unit(T::Second(0)),
+            &[
+                "seconds", "second", "s", "se", "sec", "secs", "sc", "scnd", "scnds", "secndo",
+                "sencod", "secodn", "secnod",
+            ],
+            "",
+        );
+        add(
+            G::Time,
+            W::Unit(T::Minute(0)),
+            &[
+                "minutes", "minute", "m", "mi", "min", "mins", "mn", "mns", "mnt", "mnts",
+                "minteu", "mitneu",
+            ],
+            "",
+        );
+        add(
+            G::Time,
+            W::Unit(T::Hour(0)),
+            &[
+                "hours", "hour", "h", "ho", "hou", "hr", "hrs", "hs", "horus",
+            ],
+            "",
+        );
+        add(
+            G::Time,
+            W::Unit(T::Day(0)),
+            &["days", "day", "d", "da", "ds"],
+            "",
+        );
+        add(
+            G::Time,
+            W::Unit(T::Week(0)),
+            &["weeks", "week", "w", "we", "wee", "wk", "wks"],
+            "",
+        );
+        add(
+            G::Time,
+            W::Unit(T::Month(0)),
+            &["months", "month", "mo", "mont", "mnth", "mnths"],
+            "",
+        );
+        add(
+            G::Time,
+            W::Unit(T::Year(0)),
+            &["years", "year", "y", "ye", "yea", "yr", "yrs", "ys"],
+            "",
+        );
+        add(G::Time, W::Tomorrow, &["tomorrow", "tommorow"], "");
+        add(
+            G::Time,
+            W::Next,
+            &["next"],
+            "next friday is the one after this friday",
+        );
+        add(
+            G::Time,
+            W::After,
+            &["in", "after"],
+            "in 2 hours, after 3 days, the day after tomorrow",
+        );
+        add(
+            G::Time,
+            W::This,
+            &["this", "coming", "on"],
+            "this friday, this evening",
+        );
+        add(
+            G::Time,
+            W::Between,
+            &["between", "from"],
+            "between 9am and 5pm, from 22:00 to 6:00",
+        );
+        add(G::Time, W::Article, &["the", "a", "an"], "in a week");
+
+        let day_parts: [(&[&str], u32, u32); 7] = [
+            (&["noon", "midday"], 12, 0),
+            (&["midnight"], 0, 0),
+            (&["morning"], 9, 0),
+            (&["afternoon", "arvo"], 15, 0),
+            (&["evening"], 19, 0),
+            (&["tonight", "tonite"], 21, 0),
+            (&["eod", "end-of-day"], 17, 0),
+        ];
+        for (names, hour, min) in day_parts {
+            let time = NaiveTime::from_hms_opt(hour, min, 0).unwrap_or_default();
+            add(G::DayPart, W::DayPart(time), names, "");
+        }
+
+        add(
+            G::WeekDay,
+            W::WeekDay(SUNDAY),
+            &["sunday", "su", "sun", "sn", "snd"],
+            "",
+        );
+        add(
+            G::WeekDay,
+            W::WeekDay(MONDAY),
+            &["monday", "mon", "md", "mnd"],
+            "",
+        );
+        add(
+            G::WeekDay,
+            W::WeekDay(TUESDAY),
+            &["tuesday", "tu", "tue", "tues", "tsd"],
+            "",
+        );
+        add(
+            G::WeekDay,
+            W::WeekDay(WEDNESDAY),
+            &["wednesday", "wed", "wd", "wednsd", "wdnsd"],
+            "",
+        );
+        add(
+            G::WeekDay,
+            W::WeekDay(THURSDAY),
+            &["thursday", "th", "thu", "thur", "thurs", "thrsd"],
+            "",
+        );
+        add(
+            G::WeekDay,
+            W::WeekDay(FRIDAY),
+            &["friday", "fr", "fri", "fd", "frd"],
+            "",
+        );
+        add(
+            G::WeekDay,
+            W::WeekDay(SATURDAY),
+            &["saturday", "sa", "sat", "st", "strd"],
+            "",
+        );
+        add(
+            G::WeekDay,
+            W::WeekDay(MONDAY | TUESDAY | WEDNESDAY | THURSDAY | FRIDAY),
+            &[
+                "work",
+                "wrk",
+                "business",
+                "biz",
+                "busy",
+                "workweek",
+                "work-week",
+                "workdays",
+                "weekdays",
+            ],
+            "",
+        );
+        add(
+            G::WeekDay,
+            W::WeekDay(SUNDAY | SATURDAY),
+            &[
+                "weekend", "wknd", "wkd", "break", "brk", "holiday", "rest", "week-end",
+            ],
+            "",
+        );
+
+        let months: [&[&str]; 12] = [
+            &["january", "jan"],
+            &["february", "feb"],
+            &["march", "mar"],
+            &["april", "apr", "aprl"],
+            &["may"],
+            &["june", "jun"],
+            &["july", "jul"],
+            &["august", "aug"],
+            &["september", "sep", "sept"],
+            &["october", "oct"],
+            &["november", "nov"],
+            &["december", "dec"],
+        ];
+        for (i, names) in months.into_iter().enumerate() {
+            add(G::Month, W::Month(i as u32), names, "");
+        }
+
+        add(
+            G::Repeat,
+            W::Repeat,
+            &["repeat", "rep", "repe", "repea", "rp", "times"],
+            "",
+        );
+        add(
+            G::Repeat,
+            W::RepeatTimes(0),
+            &[
+                "repeating",
+                "repetitive",
+                "every",
+                "loop",
+                "looping",
+                "infinite",
+                "ongoing",
+                "recurring",
+                "cyclic",
+                "series",
+            ],
+            "every 3 days, every other day, every 2nd week",
+        );
+        add(G::Repeat, W::RepeatTimes(2), &["twice"], "");
+        add(G::Repeat, W::Times, &["x"], "1x, x1, 2x, x2...");
+        add(G::Repeat, W::Every(T::Hour(0)), &["hourly"], "");
+        add(G::Repeat, W::Every(T::Day(0)), &["daily"], "");
+        add(G::Repeat, W::Every(T::Week(0)), &["weekly"], "");
+        add(G::Repeat, W::Every(T::Month(0)), &["monthly"], "");
+        add(
+            G::Repeat,
+            W::Every(T::Year(0)),
+            &["yearly", "annual", "annually", "anual", "anually"],
+            "",
+        );
+        add(
+            G::Repeat,
+            W::Skip,
+            &[
+                "skip",
+                "sk",
+                "skp",
+                "snooze",
+                "snz",
+                "skip-next",
+                "sk-next",
+                "skp-next",
+                "snooze-next",
+                "snz-next",
+            ],
+            "",
+        );
+        add(
+            G::Repeat,
+            W::CatchUp,
+            &["catch-up", "catchup", "missed-policy"],
+            "followed by once, each or drop",
+        );
+        add(
+            G::Repeat,
+            W::Cron,
+            &["cron", "crontab"],
+            "followed by quoted expression",
+        );
+
+        let numbers = [
+            "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+        ];
+        for (i, name) in numbers.into_iter().enumerate() {
+            add(G::Number, W::Number(i as u32), &[name], "");
+        }
+
+        add(
+            G::Command,
+            W::Undo,
+            &["undo", "goback", "go-back"],
+            "changes made by daemon firing reminders are kept",
+        );
+        add(
+            G::Command,
+            W::UnFire,
+            &["unfire", "un-fire", "rearm", "re-arm"],
+            "also undo fire, re-arms reminder daemon last fired",
+        );
+        add(G::Command, W::Redo, &["redo", "rdo"], "");
+        add(
+            G::Command,
+            W::History,
+            &["history", "hist", "log", "audit"],
+            "every change, who made it and whether it was undone",
+        );
+        add(
+            G::Command,
+            W::Edit,
+            &["edit", "ed", "change", "modify", "update"],
+            "",
+        );
+        add(
+            G::Command,
+            W::Clear,
+            &[
+                "clear",
+                "clean",
+                "cls",
+                "clr",
+                "remove-all",
+                "rm-all",
+                "del-all",
+                "delete-all",
+                "erase-all",
+                "rmv-all",
+                "dlt-all",
+            ],
+            "",
+        );
+        add(
+            G::Command,
+            W::Remove,
+            &[
+                "remove", "r", "re", "rem", "remo", "remov", "rm", "rmv", "delete", "de", "del",
+                "dele", "delet", "dl", "dlt", "erase", "forget", "forgt", "frgt",
+            ],
+            "",
+        );
+        add(
+            G::Command,
+            W::List,
+            &[
+                "list",
+                "l",
+                "li",
+                "lis",
+                "ls",
+                "reminders",
+                "all",
+                "see",
+                "everything",
+            ],
+            "",
+        );
+        add(
+            G::Command,
+            W::Explain,
+            &["explain", "dry-run", "--dry-run"],
+            "shows how rest of arguments are understood, saves nothing",
+        );
+        add(
+            G::Command,
+            W::Agenda,
+            &["agenda", "agd", "calendar"],
+            "followed by today, tomorrow, week, month, 10d, 2w or 2026-10-01..2026-10-14",
+        );
+        add(G::Command, W::Help, &["help", "hlp"], "also h on its own");
+        add(
+            G::Command,
+            W::Doctor,
+            &["doctor", "doc", "check", "fsck"],
+            "report unreadable reminders, doctor fix repairs them",
+        );
+        add(
+            G::Command,
+            W::Missed,
+            &["missed", "mised"],
+            "reminders that came due while daemon was not running",
+        );
+        vocab
+    }
+}
+
+impl Vocab {
+    // makes new word mean the same as existing one, e.g. ("woche", "week")
+    // a built in name can be taken over too, ("m", "month") makes m mean month
+    pub fn add_synonym(&mut self, name: &str, existing: &str) -> Result<(), String> {
+        let name = name.to_lowercase();
+        let existing = existing.to_lowercase();
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Err(format!("word \"{name}\" must be a single word"));
+        }
+        let idx = self
+            .entries
+            .iter()
+            .position(|entry| entry.names.contains(&existing))
+            .ok_or(format!("\"{existing}\" is not a known word"))?;
+        for entry in &mut self.entries {
+            entry.names.retain(|other| *other != name);
+        }
+        self.entries[idx].names.push(name);
+        Ok(())
+    }
+
+    // moves a named time of day ([times] in config), new names can't be added this way
+    pub fn set_day_part(&mut self, name: &str, time: NaiveTime) -> Result<(), String> {
+        let name = name.to_lowercase();
+        let entry = self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.group == Group::DayPart && entry.names.contains(&name));
+        match entry {
+            Some(entry) => {
+                entry.word = Word::DayPart(time);
+                Ok(())
+            }
+            None => Err(format!(
+                "\"{name}\" is not a time of day, expected one of {}",
+                self.entries
+                    .iter()
+                    .filter(|entry| entry.group == Group::DayPart)
+                    .map(|entry| entry.names[0].as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        }
+    }
+
+    // exact name, then unique prefix, then closest word within a typo or two
+    // prefixes and typos are only tried for words outside commands and times of day
+    pub fn lookup(&self, str: &str) -> Option<Word> {
+        let str = str.to_lowercase();
+        let exact = self.entries.iter().find(|entry| entry.names.contains(&str));
+        if let Some(entry) = exact {
+            return Some(entry.word.clone());
+        }
+        let len = str.chars().count();
+        if len < MIN_PREFIX || str.contains(char::is_whitespace) {
+            return None;
+        }
+        let fuzzy = || {
+            self.entries
+                .iter()
+                .filter(|entry| !matches!(entry.group, Group::Command | Group::DayPart))
+        };
+        // closest names by missing letters or edit distance, must all mean the same
+        let closest = |distances: Vec<(usize, &Word)>| {
+            let best = distances.iter().map(|(distance, _)| *distance).min()?;
+            let mut words = distances
+                .into_iter()
+                .filter(|(distance, _)| *distance == best)
+                .map(|(_, word)| word);
+            let word = words.next()?;
+            words.all(|other| other == word).then(|| word.clone())
+        };
+        // shortest completion wins, week -> weeks rather than weekend
+        let prefixed = fuzzy()
+            .flat_map(|entry| entry.names.iter().map(move |name| (name, &entry.word)))
+            .filter(|(name, _)| name.starts_with(&str))
+            .map(|(name, word)| (name.len() - str.len(), word))
+            .collect::<Vec<_>>();
+        if !prefixed.is_empty() {
+            return closest(prefixed);
+        }
+        let max_distance = match len {
+            MIN_TYPO_2.. => 2,
+            MIN_TYPO_1.. => 1,
+            _ => return None,
+        };
+        closest(
+            fuzzy()
+                .flat_map(|entry| entry.names.iter().map(move |name| (name, &entry.word)))
+                .map(|(name, word)| (edit_distance(&str, name), word))
+                .filter(|(distance, _)| *distance <= max_distance)
+                .collect(),
+        )
+    }
+
+    // combined weekdays like mon,thu or sa+su, parts can be as short as 2 letters (mo-th)
+    pub fn weekdays(&self, str: &str) -> Option<u8> {
+        let str = str.to_lowercase();
+        let parts = str
+            .split([',', '|', '+', '/', '_', '\\', '-'])
+            .collect::<Vec<_>>();
+        if parts.len() < 2 {
+            return None;
+        }
+        let weekday = |part: &str| {
+            let mut days = self
+                .entries
+                .iter()
+                .filter(|entry| entry.group == Group::WeekDay)
+                .filter(|entry| {
+                    entry.names.iter().any(|name| name == part)
+                        || (part.len() >= 2
+                            && entry.names[0].starts_with(part)
+                            && matches!(entry.word, Word::WeekDay(bits) if bits.count_ones() == 1))
+                })
+                .map(|entry| entry.word.clone());
+            match (days.next(), days.next()) {
+                (Some(Word::WeekDay(bits)), None) => Some(bits),
+                _ => None,
+            }
+        };
+        parts
+            .into_iter()
+            .try_fold(0, |days, part| Some(days | weekday(part)?))
+    }
+
+    // aliases section of help
+    pub fn help(&self) -> String {
+        let mut help = String::from(
+            "Aliases (words can be shortened to 4+ letters, small typos in longer ones are forgiven):\n",
+        );
+        for group in Group::ALL {
+            help += &format!("    {}:\n", group.title());
+            let entries = self.entries.iter().filter(|entry| entry.group == group);
+            // one, two, three... fit on a single line
+            if group == Group::Number {
+                let names = entries.flat_map(|entry| &entry.names);
+                help += &format!(
+                    "        - {}\n\n",
+                    names.cloned().collect::<Vec<_>>().join(", ")
+                );
+                continue;
+            }
+            for entry in entries {
+                help += &format!("        - {}", entry.names.join(" | "));
+                if let Word::DayPart(time) = entry.word {
+                    help += &format!(" ({})", time.format("%-H:%M"));
+                } else if !entry.note.is_empty() {
+                    help += &format!(" ({})", entry.note);
+                }
+                help += "\n";
+            }
+            if group == Group::WeekDay {
+                help +=
+                    "        - mon,thu | mo-th | sa+su | tu/th (combined, any of , | + / _ \\ -)\n";
+            }
+            help += "\n";
+        }
+        help
+    }
+}